@@ -0,0 +1,348 @@
+use crate::cache::temporary_path;
+use crate::clients::account::AccountClient;
+use crate::clients::HttpClient;
+use crate::models::account::Session;
+use crate::transport::HttpResponse;
+use crate::{OpenLibraryError, OpenLibraryErrorResponse};
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use http::header::LOCATION;
+use http::StatusCode;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, RwLock};
+use url::Url;
+
+const LOGIN_PATH: &str = "/account/login";
+
+/// Marks a client which hasn't logged in. See [`OpenLibraryClient::login`](crate::OpenLibraryClient::login).
+#[derive(Clone, Copy, Debug)]
+pub struct Anonymous;
+
+/// Marks a client holding a session, which allows changes to the account such as editing the
+/// reading log, rating works and managing lists.
+#[derive(Clone, Copy, Debug)]
+pub struct Authenticated;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Credentials {
+    pub username: String,
+    pub password: String,
+}
+
+impl Credentials {
+    pub fn new(username: &str, password: &str) -> Self {
+        Self {
+            username: username.to_string(),
+            password: password.to_string(),
+        }
+    }
+}
+
+/// Why Open Library refused to log in, parsed from the error code in its response.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LoginFailure {
+    /// No account exists with the given username.
+    UnknownAccount,
+    IncorrectPassword,
+    /// The account's email address hasn't been verified yet.
+    UnverifiedAccount,
+    BlockedAccount,
+    /// Too many failed attempts, the account is locked for a while.
+    LockedAccount,
+    /// Open Library accepted the login without setting a session cookie.
+    MissingSession,
+    /// An error code this client doesn't recognise.
+    Other(String),
+}
+
+impl LoginFailure {
+    pub(crate) fn from_code(code: &str) -> Self {
+        match code {
+            "account_user_notfound" => LoginFailure::UnknownAccount,
+            "account_incorrect_password" | "account_bad_password" => {
+                LoginFailure::IncorrectPassword
+            }
+            "account_not_verified" => LoginFailure::UnverifiedAccount,
+            "account_blocked" => LoginFailure::BlockedAccount,
+            "account_locked" => LoginFailure::LockedAccount,
+            code => LoginFailure::Other(code.to_string()),
+        }
+    }
+}
+
+impl Display for LoginFailure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoginFailure::UnknownAccount => write!(f, "no account exists with that username"),
+            LoginFailure::IncorrectPassword => write!(f, "the password is incorrect"),
+            LoginFailure::UnverifiedAccount => write!(f, "the account has not been verified"),
+            LoginFailure::BlockedAccount => write!(f, "the account is blocked"),
+            LoginFailure::LockedAccount => write!(f, "the account is locked"),
+            LoginFailure::MissingSession => write!(f, "no session cookie was set"),
+            LoginFailure::Other(code) => write!(f, "{}", code),
+        }
+    }
+}
+
+/// Supplies the credentials used to log in again once the client's session has expired.
+pub trait CredentialsProvider: Send + Sync {
+    fn credentials(&self) -> BoxFuture<'_, Result<Credentials, OpenLibraryError>>;
+}
+
+impl CredentialsProvider for Credentials {
+    fn credentials(&self) -> BoxFuture<'_, Result<Credentials, OpenLibraryError>> {
+        futures::future::ready(Ok(self.clone())).boxed()
+    }
+}
+
+/// Keeps a session between runs so it can be passed back to
+/// [`OpenLibraryClientBuilder::with_session`](crate::OpenLibraryClientBuilder::with_session).
+pub trait SessionStore: Send + Sync {
+    fn load(&self) -> Result<Option<Session>, OpenLibraryError>;
+    fn save(&self, session: &Session) -> Result<(), OpenLibraryError>;
+    fn clear(&self) -> Result<(), OpenLibraryError>;
+}
+
+#[derive(Debug, Default)]
+pub struct MemorySessionStore {
+    session: Mutex<Option<Session>>,
+}
+
+impl MemorySessionStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl SessionStore for MemorySessionStore {
+    fn load(&self) -> Result<Option<Session>, OpenLibraryError> {
+        Ok(self
+            .session
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+            .clone())
+    }
+
+    fn save(&self, session: &Session) -> Result<(), OpenLibraryError> {
+        *self
+            .session
+            .lock()
+            .unwrap_or_else(|error| error.into_inner()) = Some(session.clone());
+        Ok(())
+    }
+
+    fn clear(&self) -> Result<(), OpenLibraryError> {
+        *self
+            .session
+            .lock()
+            .unwrap_or_else(|error| error.into_inner()) = None;
+        Ok(())
+    }
+}
+
+/// Stores the session as JSON in a single file, readable only by its owner on Unix since the
+/// cookie grants access to the account.
+#[derive(Clone, Debug)]
+pub struct FileSessionStore {
+    path: PathBuf,
+}
+
+impl FileSessionStore {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self { path: path.into() }
+    }
+
+    fn error(&self, action: &str, error: impl std::fmt::Display) -> OpenLibraryError {
+        OpenLibraryError::SessionStoreError {
+            reason: format!(
+                "Unable to {} session file {}: {}",
+                action,
+                self.path.display(),
+                error
+            ),
+        }
+    }
+}
+
+impl SessionStore for FileSessionStore {
+    fn load(&self) -> Result<Option<Session>, OpenLibraryError> {
+        let contents = match fs::read(&self.path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(error) => return Err(self.error("read", error)),
+        };
+
+        serde_json::from_slice(&contents)
+            .map(Some)
+            .map_err(|error| self.error("parse", error))
+    }
+
+    fn save(&self, session: &Session) -> Result<(), OpenLibraryError> {
+        let contents = serde_json::to_vec(session).map_err(|error| self.error("encode", error))?;
+        if let Some(parent) = self
+            .path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent).map_err(|error| self.error("create", error))?;
+        }
+
+        let temporary = temporary_path(&self.path);
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+        let mut file = options
+            .open(&temporary)
+            .map_err(|error| self.error("create", error))?;
+        let result = file
+            .write_all(&contents)
+            .and_then(|_| file.sync_all())
+            .and_then(|_| fs::rename(&temporary, &self.path));
+
+        if result.is_err() {
+            let _ = fs::remove_file(&temporary);
+        }
+        result.map_err(|error| self.error("write", error))
+    }
+
+    fn clear(&self) -> Result<(), OpenLibraryError> {
+        match fs::remove_file(&self.path) {
+            Err(error) if error.kind() != ErrorKind::NotFound => Err(self.error("remove", error)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Only error documents about the session itself mean the session was rejected. Others, e.g. a
+/// missing or private shelf, are permanent and logging in again wouldn't change them.
+fn rejects_session(error: &OpenLibraryErrorResponse) -> bool {
+    let message = error.error.to_lowercase();
+
+    message.contains("login") || message.contains("log in") || message.contains("session")
+}
+
+/// Holds the session shared by every sub-client built together, logging in again with the
+/// configured credentials when the session is rejected.
+#[derive(Clone)]
+pub(crate) struct Authenticator {
+    session: Arc<RwLock<Option<Session>>>,
+    provider: Option<Arc<dyn CredentialsProvider>>,
+    refreshing: Arc<tokio::sync::Mutex<()>>,
+    host: Url,
+}
+
+impl Authenticator {
+    pub(crate) fn new(
+        session: Option<Session>,
+        provider: Option<Arc<dyn CredentialsProvider>>,
+        host: Url,
+    ) -> Self {
+        Self {
+            session: Arc::new(RwLock::new(session)),
+            provider,
+            refreshing: Arc::new(tokio::sync::Mutex::new(())),
+            host,
+        }
+    }
+
+    pub(crate) fn session(&self) -> Option<Session> {
+        self.session
+            .read()
+            .unwrap_or_else(|error| error.into_inner())
+            .clone()
+    }
+
+    /// A new authenticator, independent of this one, holding the given session and logging in
+    /// again with the same credentials.
+    pub(crate) fn with_session(&self, session: Session) -> Self {
+        Self::new(Some(session), self.provider.clone(), self.host.clone())
+    }
+
+    pub(crate) fn clear_session(&self) {
+        self.set_session(None);
+    }
+
+    fn set_session(&self, session: Option<Session>) {
+        *self
+            .session
+            .write()
+            .unwrap_or_else(|error| error.into_inner()) = session;
+    }
+
+    pub(crate) fn can_refresh(&self) -> bool {
+        self.provider.is_some()
+    }
+
+    /// The current session, logging in with the configured credentials when there is none yet.
+    pub(crate) async fn require_session(
+        &self,
+        client: &HttpClient,
+    ) -> Result<Session, OpenLibraryError> {
+        if self.session().is_none() {
+            self.refresh(client, None).await?;
+        }
+
+        self.session()
+            .ok_or_else(|| OpenLibraryError::NotAuthenticated {
+                reason: "The client has no session and no credentials to log in with".to_string(),
+            })
+    }
+
+    /// Whether the response shows the session the request was sent with was missing, expired or
+    /// otherwise rejected. Forbidden responses aren't, since a valid session is refused those too.
+    pub(crate) fn is_rejected(&self, response: &HttpResponse) -> bool {
+        let redirected_to_login = response.url.path().starts_with(LOGIN_PATH)
+            || response
+                .headers
+                .get(LOCATION)
+                .and_then(|value| value.to_str().ok())
+                .is_some_and(|location| location.contains(LOGIN_PATH));
+
+        match response.status {
+            StatusCode::UNAUTHORIZED => true,
+            // Some endpoints, e.g. the reading log, answer with an error document instead
+            StatusCode::OK => {
+                redirected_to_login
+                    || serde_json::from_slice::<OpenLibraryErrorResponse>(&response.body)
+                        .is_ok_and(|error| rejects_session(&error))
+            }
+            _ => redirected_to_login,
+        }
+    }
+
+    /// Logs in again unless another request already replaced the `rejected` session meanwhile.
+    // Boxed since logging in sends a request through the client which called this
+    pub(crate) fn refresh<'a>(
+        &'a self,
+        client: &'a HttpClient,
+        rejected: Option<Session>,
+    ) -> BoxFuture<'a, Result<(), OpenLibraryError>> {
+        async move {
+            let provider = match &self.provider {
+                Some(provider) => provider,
+                None => return Ok(()),
+            };
+
+            let _refreshing = self.refreshing.lock().await;
+            if self.session() != rejected {
+                return Ok(());
+            }
+
+            let credentials = provider.credentials().await?;
+            let account = AccountClient::<Anonymous>::new(client, &self.host);
+            let session = account
+                .login(credentials.username, credentials.password)
+                .await?;
+
+            tracing::debug!(username = %session.username(), "Replaced the expired Open Library session");
+            self.set_session(Some(session));
+            Ok(())
+        }
+        .boxed()
+    }
+}
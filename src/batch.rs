@@ -0,0 +1,111 @@
+use crate::models::identifiers::OpenLibraryIdentifier;
+use crate::OpenLibraryError;
+use futures::{stream, Future, StreamExt};
+use std::collections::HashSet;
+
+/// Controls how a `get_many` call fans out its requests.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BatchOptions {
+    concurrency: usize,
+    preserve_order: bool,
+}
+
+impl Default for BatchOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: 4,
+            preserve_order: true,
+        }
+    }
+}
+
+impl BatchOptions {
+    /// The maximum number of requests in flight at once.
+    pub fn with_concurrency(self, concurrency: usize) -> Self {
+        Self {
+            concurrency: concurrency.max(1),
+            ..self
+        }
+    }
+
+    /// Return results in the order identifiers were supplied rather than the order they complete.
+    pub fn with_preserve_order(self, preserve_order: bool) -> Self {
+        Self {
+            preserve_order,
+            ..self
+        }
+    }
+}
+
+/// The outcome of a `get_many` call, one result per distinct identifier.
+#[derive(Debug)]
+pub struct BatchResponse<T> {
+    results: Vec<(OpenLibraryIdentifier, Result<T, OpenLibraryError>)>,
+}
+
+impl<T> BatchResponse<T> {
+    pub fn get(&self, identifier: &OpenLibraryIdentifier) -> Option<&Result<T, OpenLibraryError>> {
+        self.results
+            .iter()
+            .find(|(candidate, _)| candidate == identifier)
+            .map(|(_, result)| result)
+    }
+
+    pub fn iter(
+        &self,
+    ) -> impl Iterator<Item = &(OpenLibraryIdentifier, Result<T, OpenLibraryError>)> {
+        self.results.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.results.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.results.is_empty()
+    }
+}
+
+impl<T> IntoIterator for BatchResponse<T> {
+    type Item = (OpenLibraryIdentifier, Result<T, OpenLibraryError>);
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.results.into_iter()
+    }
+}
+
+pub(crate) async fn fetch_many<I, T, F, Fut>(
+    identifiers: I,
+    options: BatchOptions,
+    fetch: F,
+) -> BatchResponse<T>
+where
+    I: IntoIterator<Item = OpenLibraryIdentifier>,
+    F: Fn(OpenLibraryIdentifier) -> Fut,
+    Fut: Future<Output = Result<T, OpenLibraryError>>,
+{
+    let mut seen = HashSet::new();
+    let requests = stream::iter(
+        identifiers
+            .into_iter()
+            .filter(|identifier| seen.insert(identifier.clone()))
+            .collect::<Vec<_>>(),
+    )
+    .map(|identifier| {
+        let future = fetch(identifier.clone());
+        async move { (identifier, future.await) }
+    });
+
+    let results = match options.preserve_order {
+        true => requests.buffered(options.concurrency).collect().await,
+        false => {
+            requests
+                .buffer_unordered(options.concurrency)
+                .collect()
+                .await
+        }
+    };
+
+    BatchResponse { results }
+}
@@ -0,0 +1,550 @@
+//! A synchronous mirror of [`crate::OpenLibraryClient`] for callers without an async runtime.
+//! Enabled with the `blocking` feature.
+//!
+//! Each client drives requests on its own single threaded runtime, so calling these methods from
+//! within an async context will panic; use the async client there instead.
+
+use crate::auth::{Anonymous, Authenticated};
+use crate::batch::{BatchOptions, BatchResponse};
+use crate::cache::{CacheMode, CacheStats};
+use crate::failover::HostHealth;
+use crate::models::account::{NewList, ReadingLog, ReadingLogEntry, Session};
+use crate::models::authors::{
+    Author, AuthorDetails, AuthorResponse, AuthorWorksRequest, AuthorWorksResponse,
+};
+use crate::models::books::{BibliographyKey, Book};
+use crate::models::identifiers::{InternationalStandardBookNumber, OpenLibraryIdentifier};
+use crate::models::works::Work;
+use crate::models::{DeserializationMode, OpenLibraryResource, WithRaw};
+use crate::{clients, pagination, OpenLibraryClientBuilder, OpenLibraryError};
+use futures::StreamExt;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::runtime::Runtime;
+
+#[derive(Clone)]
+struct BlockingRuntime(Arc<Runtime>);
+
+impl BlockingRuntime {
+    fn new() -> Result<Self, OpenLibraryError> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|error| OpenLibraryError::InternalError {
+                reason: format!("Unable to start the blocking client runtime: {}", error),
+            })?;
+
+        Ok(Self(Arc::new(runtime)))
+    }
+
+    fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.0.block_on(future)
+    }
+}
+
+pub struct OpenLibraryClient<A = Anonymous> {
+    pub account: AccountClient<A>,
+    pub author: AuthorClient,
+    pub books: BooksClient,
+    pub works: WorksClient,
+    inner: crate::OpenLibraryClient<A>,
+    runtime: BlockingRuntime,
+}
+
+impl<A> Clone for OpenLibraryClient<A> {
+    fn clone(&self) -> Self {
+        Self::new(self.inner.clone(), self.runtime.clone())
+    }
+}
+
+impl OpenLibraryClient {
+    pub fn builder() -> OpenLibraryClientBuilder {
+        crate::OpenLibraryClient::builder()
+    }
+
+    pub(crate) fn from(inner: crate::OpenLibraryClient) -> Result<Self, OpenLibraryError> {
+        Ok(Self::new(inner, BlockingRuntime::new()?))
+    }
+
+    pub fn login(
+        &self,
+        username: String,
+        password: String,
+    ) -> Result<OpenLibraryClient<Authenticated>, OpenLibraryError> {
+        let inner = self
+            .runtime
+            .block_on(self.inner.login(username, password))?;
+
+        Ok(OpenLibraryClient::new(inner, self.runtime.clone()))
+    }
+
+    pub fn authenticated(&self) -> Result<OpenLibraryClient<Authenticated>, OpenLibraryError> {
+        Ok(OpenLibraryClient::new(
+            self.inner.authenticated()?,
+            self.runtime.clone(),
+        ))
+    }
+}
+
+impl OpenLibraryClient<Authenticated> {
+    pub fn logout(self) -> Result<OpenLibraryClient, OpenLibraryError> {
+        let inner = self.runtime.block_on(self.inner.logout())?;
+
+        Ok(OpenLibraryClient::new(inner, self.runtime))
+    }
+}
+
+impl<A> OpenLibraryClient<A> {
+    fn new(inner: crate::OpenLibraryClient<A>, runtime: BlockingRuntime) -> Self {
+        Self {
+            account: AccountClient {
+                inner: inner.account.clone(),
+                runtime: runtime.clone(),
+            },
+            author: AuthorClient {
+                inner: inner.author.clone(),
+                runtime: runtime.clone(),
+            },
+            books: BooksClient {
+                inner: inner.books.clone(),
+                runtime: runtime.clone(),
+            },
+            works: WorksClient {
+                inner: inner.works.clone(),
+                runtime: runtime.clone(),
+            },
+            inner,
+            runtime,
+        }
+    }
+
+    pub fn with_cache_mode(&self, cache_mode: CacheMode) -> OpenLibraryClient<A> {
+        Self::new(self.inner.with_cache_mode(cache_mode), self.runtime.clone())
+    }
+
+    pub fn with_timeout(&self, timeout: Duration) -> OpenLibraryClient<A> {
+        Self::new(self.inner.with_timeout(timeout), self.runtime.clone())
+    }
+
+    pub fn with_deserialization_mode(&self, mode: DeserializationMode) -> OpenLibraryClient<A> {
+        Self::new(
+            self.inner.with_deserialization_mode(mode),
+            self.runtime.clone(),
+        )
+    }
+
+    pub fn session(&self) -> Option<Session> {
+        self.inner.session()
+    }
+
+    pub fn cache_stats(&self) -> Option<CacheStats> {
+        self.inner.cache_stats()
+    }
+
+    pub fn host_health(&self) -> Option<Vec<HostHealth>> {
+        self.inner.host_health()
+    }
+}
+
+pub struct AccountClient<A = Anonymous> {
+    inner: clients::account::AccountClient<A>,
+    runtime: BlockingRuntime,
+}
+
+impl<A> Clone for AccountClient<A> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            runtime: self.runtime.clone(),
+        }
+    }
+}
+
+impl<A> AccountClient<A> {
+    pub fn login(&self, username: String, password: String) -> Result<Session, OpenLibraryError> {
+        self.runtime.block_on(self.inner.login(username, password))
+    }
+
+    pub fn whoami(&self) -> Result<String, OpenLibraryError> {
+        self.runtime.block_on(self.inner.whoami())
+    }
+
+    pub fn get_already_read(
+        &self,
+        username: String,
+    ) -> Result<Vec<ReadingLogEntry>, OpenLibraryError> {
+        self.runtime.block_on(self.inner.get_already_read(username))
+    }
+
+    pub fn get_already_read_raw(&self, username: String) -> Result<Value, OpenLibraryError> {
+        self.runtime
+            .block_on(self.inner.get_already_read_raw(username))
+    }
+
+    pub fn get_already_read_with_raw(
+        &self,
+        username: String,
+    ) -> Result<WithRaw<Vec<ReadingLogEntry>>, OpenLibraryError> {
+        self.runtime
+            .block_on(self.inner.get_already_read_with_raw(username))
+    }
+
+    pub fn get_currently_reading(
+        &self,
+        username: String,
+    ) -> Result<Vec<ReadingLogEntry>, OpenLibraryError> {
+        self.runtime
+            .block_on(self.inner.get_currently_reading(username))
+    }
+
+    pub fn get_currently_reading_raw(&self, username: String) -> Result<Value, OpenLibraryError> {
+        self.runtime
+            .block_on(self.inner.get_currently_reading_raw(username))
+    }
+
+    pub fn get_currently_reading_with_raw(
+        &self,
+        username: String,
+    ) -> Result<WithRaw<Vec<ReadingLogEntry>>, OpenLibraryError> {
+        self.runtime
+            .block_on(self.inner.get_currently_reading_with_raw(username))
+    }
+
+    pub fn get_want_to_read(
+        &self,
+        username: String,
+    ) -> Result<Vec<ReadingLogEntry>, OpenLibraryError> {
+        self.runtime.block_on(self.inner.get_want_to_read(username))
+    }
+
+    pub fn get_want_to_read_raw(&self, username: String) -> Result<Value, OpenLibraryError> {
+        self.runtime
+            .block_on(self.inner.get_want_to_read_raw(username))
+    }
+
+    pub fn get_want_to_read_with_raw(
+        &self,
+        username: String,
+    ) -> Result<WithRaw<Vec<ReadingLogEntry>>, OpenLibraryError> {
+        self.runtime
+            .block_on(self.inner.get_want_to_read_with_raw(username))
+    }
+
+    pub fn get_reading_log_paginated(
+        &self,
+        reading_log: ReadingLog,
+        username: String,
+    ) -> Result<Paginated<ReadingLogEntry>, OpenLibraryError> {
+        Ok(Paginated::new(
+            self.inner
+                .get_reading_log_paginated(reading_log, username)?,
+            self.runtime.clone(),
+        ))
+    }
+}
+
+impl AccountClient<Authenticated> {
+    pub fn logout(&self) -> Result<(), OpenLibraryError> {
+        self.runtime.block_on(self.inner.logout())
+    }
+
+    pub fn add_to_reading_log(
+        &self,
+        work: &OpenLibraryIdentifier,
+        reading_log: ReadingLog,
+    ) -> Result<(), OpenLibraryError> {
+        self.runtime
+            .block_on(self.inner.add_to_reading_log(work, reading_log))
+    }
+
+    pub fn remove_from_reading_log(
+        &self,
+        work: &OpenLibraryIdentifier,
+        reading_log: ReadingLog,
+    ) -> Result<(), OpenLibraryError> {
+        self.runtime
+            .block_on(self.inner.remove_from_reading_log(work, reading_log))
+    }
+
+    pub fn rate(&self, work: &OpenLibraryIdentifier, rating: u8) -> Result<(), OpenLibraryError> {
+        self.runtime.block_on(self.inner.rate(work, rating))
+    }
+
+    pub fn remove_rating(&self, work: &OpenLibraryIdentifier) -> Result<(), OpenLibraryError> {
+        self.runtime.block_on(self.inner.remove_rating(work))
+    }
+
+    pub fn create_list(&self, list: &NewList) -> Result<OpenLibraryIdentifier, OpenLibraryError> {
+        self.runtime.block_on(self.inner.create_list(list))
+    }
+
+    pub fn add_to_list(
+        &self,
+        list: &OpenLibraryIdentifier,
+        seeds: &[OpenLibraryResource],
+    ) -> Result<(), OpenLibraryError> {
+        self.runtime.block_on(self.inner.add_to_list(list, seeds))
+    }
+
+    pub fn remove_from_list(
+        &self,
+        list: &OpenLibraryIdentifier,
+        seeds: &[OpenLibraryResource],
+    ) -> Result<(), OpenLibraryError> {
+        self.runtime
+            .block_on(self.inner.remove_from_list(list, seeds))
+    }
+
+    pub fn delete_list(&self, list: &OpenLibraryIdentifier) -> Result<(), OpenLibraryError> {
+        self.runtime.block_on(self.inner.delete_list(list))
+    }
+}
+
+#[derive(Clone)]
+pub struct AuthorClient {
+    inner: clients::author::AuthorClient,
+    runtime: BlockingRuntime,
+}
+
+impl AuthorClient {
+    pub fn get(
+        &self,
+        identifier: OpenLibraryIdentifier,
+    ) -> Result<AuthorDetails, OpenLibraryError> {
+        self.runtime.block_on(self.inner.get(identifier))
+    }
+
+    pub fn get_raw(&self, identifier: OpenLibraryIdentifier) -> Result<Value, OpenLibraryError> {
+        self.runtime.block_on(self.inner.get_raw(identifier))
+    }
+
+    pub fn get_with_raw(
+        &self,
+        identifier: OpenLibraryIdentifier,
+    ) -> Result<WithRaw<AuthorDetails>, OpenLibraryError> {
+        self.runtime.block_on(self.inner.get_with_raw(identifier))
+    }
+
+    pub fn get_many<I>(&self, identifiers: I, options: BatchOptions) -> BatchResponse<AuthorDetails>
+    where
+        I: IntoIterator<Item = OpenLibraryIdentifier>,
+    {
+        self.runtime
+            .block_on(self.inner.get_many(identifiers, options))
+    }
+
+    pub fn get_works<T>(&self, request: T) -> Result<AuthorWorksResponse, OpenLibraryError>
+    where
+        T: TryInto<AuthorWorksRequest>,
+    {
+        self.runtime.block_on(self.inner.get_works(request))
+    }
+
+    pub fn get_works_raw<T>(&self, request: T) -> Result<Value, OpenLibraryError>
+    where
+        T: TryInto<AuthorWorksRequest>,
+    {
+        self.runtime.block_on(self.inner.get_works_raw(request))
+    }
+
+    pub fn get_works_with_raw<T>(
+        &self,
+        request: T,
+    ) -> Result<WithRaw<AuthorWorksResponse>, OpenLibraryError>
+    where
+        T: TryInto<AuthorWorksRequest>,
+    {
+        self.runtime
+            .block_on(self.inner.get_works_with_raw(request))
+    }
+
+    pub fn get_works_paginated<T>(&self, request: T) -> Result<Paginated<Work>, OpenLibraryError>
+    where
+        T: TryInto<AuthorWorksRequest>,
+    {
+        Ok(Paginated::new(
+            self.inner.get_works_paginated(request)?,
+            self.runtime.clone(),
+        ))
+    }
+
+    pub fn search(&self, author_name: &str) -> Result<AuthorResponse, OpenLibraryError> {
+        self.runtime.block_on(self.inner.search(author_name))
+    }
+
+    pub fn search_raw(&self, author_name: &str) -> Result<Value, OpenLibraryError> {
+        self.runtime.block_on(self.inner.search_raw(author_name))
+    }
+
+    pub fn search_with_raw(
+        &self,
+        author_name: &str,
+    ) -> Result<WithRaw<AuthorResponse>, OpenLibraryError> {
+        self.runtime
+            .block_on(self.inner.search_with_raw(author_name))
+    }
+
+    pub fn search_paginated(&self, author_name: &str) -> Paginated<Author> {
+        Paginated::new(
+            self.inner.search_paginated(author_name),
+            self.runtime.clone(),
+        )
+    }
+}
+
+#[derive(Clone)]
+pub struct BooksClient {
+    inner: clients::books::BooksClient,
+    runtime: BlockingRuntime,
+}
+
+impl BooksClient {
+    pub fn by_isbn(&self, isbn: InternationalStandardBookNumber) -> Result<Book, OpenLibraryError> {
+        self.runtime.block_on(self.inner.by_isbn(isbn))
+    }
+
+    pub fn by_isbn_raw(
+        &self,
+        isbn: InternationalStandardBookNumber,
+    ) -> Result<Value, OpenLibraryError> {
+        self.runtime.block_on(self.inner.by_isbn_raw(isbn))
+    }
+
+    pub fn by_isbn_with_raw(
+        &self,
+        isbn: InternationalStandardBookNumber,
+    ) -> Result<WithRaw<Book>, OpenLibraryError> {
+        self.runtime.block_on(self.inner.by_isbn_with_raw(isbn))
+    }
+
+    pub fn get(&self, identifier: OpenLibraryIdentifier) -> Result<Book, OpenLibraryError> {
+        self.runtime.block_on(self.inner.get(identifier))
+    }
+
+    pub fn get_raw(&self, identifier: OpenLibraryIdentifier) -> Result<Value, OpenLibraryError> {
+        self.runtime.block_on(self.inner.get_raw(identifier))
+    }
+
+    pub fn get_with_raw(
+        &self,
+        identifier: OpenLibraryIdentifier,
+    ) -> Result<WithRaw<Book>, OpenLibraryError> {
+        self.runtime.block_on(self.inner.get_with_raw(identifier))
+    }
+
+    pub fn get_many<I>(&self, identifiers: I, options: BatchOptions) -> BatchResponse<Book>
+    where
+        I: IntoIterator<Item = OpenLibraryIdentifier>,
+    {
+        self.runtime
+            .block_on(self.inner.get_many(identifiers, options))
+    }
+
+    pub fn search<'a, T: Into<&'a Vec<BibliographyKey>>>(
+        &self,
+        identifiers: T,
+    ) -> Result<HashMap<BibliographyKey, Book>, OpenLibraryError> {
+        self.runtime.block_on(self.inner.search(identifiers))
+    }
+
+    pub fn search_raw<'a, T: Into<&'a Vec<BibliographyKey>>>(
+        &self,
+        identifiers: T,
+    ) -> Result<Value, OpenLibraryError> {
+        self.runtime.block_on(self.inner.search_raw(identifiers))
+    }
+
+    pub fn search_with_raw<'a, T: Into<&'a Vec<BibliographyKey>>>(
+        &self,
+        identifiers: T,
+    ) -> Result<WithRaw<HashMap<BibliographyKey, Book>>, OpenLibraryError> {
+        self.runtime
+            .block_on(self.inner.search_with_raw(identifiers))
+    }
+}
+
+#[derive(Clone)]
+pub struct WorksClient {
+    inner: clients::works::WorksClient,
+    runtime: BlockingRuntime,
+}
+
+impl WorksClient {
+    pub fn get_many<I>(&self, identifiers: I, options: BatchOptions) -> BatchResponse<Work>
+    where
+        I: IntoIterator<Item = OpenLibraryIdentifier>,
+    {
+        self.runtime
+            .block_on(self.inner.get_many(identifiers, options))
+    }
+
+    pub fn get(&self, identifier: &OpenLibraryIdentifier) -> Result<Work, OpenLibraryError> {
+        self.runtime.block_on(self.inner.get(identifier))
+    }
+
+    pub fn get_raw(&self, identifier: &OpenLibraryIdentifier) -> Result<Value, OpenLibraryError> {
+        self.runtime.block_on(self.inner.get_raw(identifier))
+    }
+
+    pub fn get_with_raw(
+        &self,
+        identifier: &OpenLibraryIdentifier,
+    ) -> Result<WithRaw<Work>, OpenLibraryError> {
+        self.runtime.block_on(self.inner.get_with_raw(identifier))
+    }
+}
+
+/// An iterator over every item of a paginated endpoint, fetching further pages as needed.
+pub struct Paginated<T> {
+    stream: pagination::Paginated<T>,
+    runtime: BlockingRuntime,
+}
+
+impl<T> Paginated<T>
+where
+    T: Send + 'static,
+{
+    fn new(stream: pagination::Paginated<T>, runtime: BlockingRuntime) -> Self {
+        Self { stream, runtime }
+    }
+
+    pub fn with_page_size(self, page_size: u32) -> Self {
+        Self {
+            stream: self.stream.with_page_size(page_size),
+            ..self
+        }
+    }
+
+    pub fn with_max_items(self, max_items: usize) -> Self {
+        Self {
+            stream: self.stream.with_max_items(max_items),
+            ..self
+        }
+    }
+
+    pub fn with_stop_predicate<P>(self, predicate: P) -> Self
+    where
+        P: Fn(&T) -> bool + Send + Sync + 'static,
+    {
+        Self {
+            stream: self.stream.with_stop_predicate(predicate),
+            ..self
+        }
+    }
+}
+
+impl<T> Iterator for Paginated<T>
+where
+    T: Send + 'static,
+{
+    type Item = Result<T, OpenLibraryError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let stream = &mut self.stream;
+        self.runtime.block_on(stream.next())
+    }
+}
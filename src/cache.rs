@@ -0,0 +1,375 @@
+use crate::Endpoint;
+use http::header::{HeaderName, HeaderValue};
+use http::HeaderMap;
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use url::Url;
+
+/// A raw response body stored by a [`CacheStore`] along with when it stops being fresh and the
+/// validators needed to revalidate it once it has. The headers and the URL the response was served
+/// from are kept for caches replaying whole responses, e.g. [`CacheLayer`](crate::service::CacheLayer).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CacheEntry {
+    pub body: Vec<u8>,
+    pub expires_at: SystemTime,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub headers: HeaderMap,
+    pub url: Option<Url>,
+}
+
+impl CacheEntry {
+    pub fn is_fresh(&self) -> bool {
+        self.expires_at > SystemTime::now()
+    }
+}
+
+/// Storage for cached responses keyed by request. Entries are returned even once they have
+/// expired, it's up to the caller to decide whether a stale entry is still useful.
+pub trait CacheStore: Send + Sync {
+    fn get(&self, key: &str) -> Option<CacheEntry>;
+    fn put(&self, key: &str, entry: CacheEntry);
+    fn remove(&self, key: &str);
+}
+
+/// An in-memory store which evicts the least recently used entry once `capacity` is reached.
+pub struct MemoryCacheStore {
+    entries: Mutex<LruCache<String, CacheEntry>>,
+}
+
+impl MemoryCacheStore {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: Mutex::new(LruCache::new(
+                NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN),
+            )),
+        }
+    }
+}
+
+impl CacheStore for MemoryCacheStore {
+    fn get(&self, key: &str) -> Option<CacheEntry> {
+        self.entries
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+            .get(key)
+            .cloned()
+    }
+
+    fn put(&self, key: &str, entry: CacheEntry) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+            .put(key.to_string(), entry);
+    }
+
+    fn remove(&self, key: &str) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+            .pop(key);
+    }
+}
+
+static TEMPORARY_FILES: AtomicU64 = AtomicU64::new(0);
+
+/// A temporary file next to `path` to write to before renaming it into place. Concurrent writers,
+/// within this process or others, each need their own.
+pub(crate) fn temporary_path(path: &Path) -> PathBuf {
+    path.with_extension(format!(
+        "{}.{}.tmp",
+        std::process::id(),
+        TEMPORARY_FILES.fetch_add(1, Ordering::Relaxed)
+    ))
+}
+
+/// A persistent store keeping one file per entry within `directory`. Any I/O failure is treated
+/// as a cache miss rather than failing the request. Entries are never evicted, expired ones are
+/// only replaced once fetched again, so clearing out `directory` is left to the caller.
+pub struct DiskCacheStore {
+    directory: PathBuf,
+}
+
+#[derive(Deserialize, Serialize)]
+struct DiskEntryHeader {
+    key: String,
+    expires_at: u64,
+    #[serde(default)]
+    etag: Option<String>,
+    #[serde(default)]
+    last_modified: Option<String>,
+    #[serde(default)]
+    headers: Vec<(String, String)>,
+    #[serde(default)]
+    url: Option<String>,
+}
+
+impl DiskCacheStore {
+    pub fn new<P: Into<PathBuf>>(directory: P) -> Self {
+        Self {
+            directory: directory.into(),
+        }
+    }
+
+    // The file name needs to be stable across runs, which rules out the standard library hasher
+    fn path(&self, key: &str) -> PathBuf {
+        let hash = key.bytes().fold(0xcbf29ce484222325_u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        });
+
+        self.directory.join(format!("{:016x}.cache", hash))
+    }
+
+    fn read(&self, key: &str) -> std::io::Result<Option<CacheEntry>> {
+        let mut reader = BufReader::new(fs::File::open(self.path(key))?);
+        let mut header = String::new();
+        reader.read_line(&mut header)?;
+
+        let header: DiskEntryHeader = serde_json::from_str(header.as_str())?;
+        if header.key != key {
+            return Ok(None);
+        }
+
+        let mut body = Vec::new();
+        reader.read_to_end(&mut body)?;
+
+        Ok(Some(CacheEntry {
+            body,
+            expires_at: UNIX_EPOCH + Duration::from_secs(header.expires_at),
+            etag: header.etag,
+            last_modified: header.last_modified,
+            headers: header
+                .headers
+                .iter()
+                .filter_map(|(name, value)| {
+                    Some((
+                        HeaderName::from_bytes(name.as_bytes()).ok()?,
+                        HeaderValue::from_str(value).ok()?,
+                    ))
+                })
+                .collect(),
+            url: header.url.and_then(|url| Url::parse(url.as_str()).ok()),
+        }))
+    }
+
+    fn write(&self, key: &str, entry: &CacheEntry) -> std::io::Result<()> {
+        fs::create_dir_all(&self.directory)?;
+
+        let header = DiskEntryHeader {
+            key: key.to_string(),
+            expires_at: entry
+                .expires_at
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            etag: entry.etag.clone(),
+            last_modified: entry.last_modified.clone(),
+            // Values which aren't valid UTF-8 can't be written out as JSON strings, so are dropped
+            headers: entry
+                .headers
+                .iter()
+                .filter_map(|(name, value)| {
+                    Some((name.to_string(), value.to_str().ok()?.to_string()))
+                })
+                .collect(),
+            url: entry.url.as_ref().map(Url::to_string),
+        };
+
+        let path = self.path(key);
+        let temporary = temporary_path(&path);
+        let result = fs::File::create(&temporary).and_then(|mut file| {
+            serde_json::to_writer(&mut file, &header)?;
+            file.write_all(b"\n")?;
+            file.write_all(&entry.body)?;
+            file.sync_all()?;
+            fs::rename(&temporary, path)
+        });
+
+        if result.is_err() {
+            let _ = fs::remove_file(&temporary);
+        }
+        result
+    }
+}
+
+impl CacheStore for DiskCacheStore {
+    fn get(&self, key: &str) -> Option<CacheEntry> {
+        self.read(key).ok().flatten()
+    }
+
+    fn put(&self, key: &str, entry: CacheEntry) {
+        if let Err(error) = self.write(key, &entry) {
+            tracing::warn!("Unable to write cache entry for {}: {}", key, error);
+        }
+    }
+
+    fn remove(&self, key: &str) {
+        let _ = fs::remove_file(self.path(key));
+    }
+}
+
+/// Controls how an individual call interacts with the configured cache.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum CacheMode {
+    /// Serve fresh entries from the cache and store new responses.
+    #[default]
+    Default,
+    /// Neither read from nor write to the cache.
+    Bypass,
+    /// Always go to the network but store the new response.
+    Refresh,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub revalidations: u64,
+}
+
+#[derive(Clone)]
+pub struct CacheConfig {
+    store: Arc<dyn CacheStore>,
+    default_ttl: Duration,
+    ttls: HashMap<Endpoint, Duration>,
+}
+
+impl CacheConfig {
+    pub fn new<S>(store: S) -> Self
+    where
+        S: CacheStore + 'static,
+    {
+        Self {
+            store: Arc::new(store),
+            default_ttl: Duration::from_secs(60 * 60),
+            ttls: HashMap::new(),
+        }
+    }
+
+    pub fn with_default_ttl(self, default_ttl: Duration) -> Self {
+        Self {
+            default_ttl,
+            ..self
+        }
+    }
+
+    /// Overrides the time to live for a single endpoint, a zero duration disables caching for it.
+    pub fn with_ttl(self, endpoint: Endpoint, ttl: Duration) -> Self {
+        let mut ttls = self.ttls;
+        ttls.insert(endpoint, ttl);
+
+        Self { ttls, ..self }
+    }
+}
+
+pub(crate) enum CacheLookup {
+    Fresh(CacheEntry),
+    Stale(CacheEntry),
+    Missing,
+}
+
+#[derive(Clone)]
+pub(crate) struct ResponseCache {
+    config: CacheConfig,
+    conditional: bool,
+    hits: Arc<AtomicU64>,
+    misses: Arc<AtomicU64>,
+    revalidations: Arc<AtomicU64>,
+}
+
+impl ResponseCache {
+    pub(crate) fn new(config: CacheConfig, conditional: bool) -> Self {
+        Self {
+            config,
+            conditional,
+            hits: Arc::new(AtomicU64::new(0)),
+            misses: Arc::new(AtomicU64::new(0)),
+            revalidations: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Only keeps validators around so responses can be revalidated, never serving them without
+    /// checking with the server first.
+    pub(crate) fn conditional_only() -> Self {
+        Self::new(
+            CacheConfig::new(MemoryCacheStore::new(1000)).with_default_ttl(Duration::ZERO),
+            true,
+        )
+    }
+
+    pub(crate) fn is_conditional(&self) -> bool {
+        self.conditional
+    }
+
+    pub(crate) fn ttl(&self, endpoint: Endpoint) -> Duration {
+        match endpoint {
+            Endpoint::GetReadingLog | Endpoint::Login => Duration::ZERO,
+            _ => *self
+                .config
+                .ttls
+                .get(&endpoint)
+                .unwrap_or(&self.config.default_ttl),
+        }
+    }
+
+    #[cfg(feature = "tower")]
+    pub(crate) fn default_ttl(&self) -> Duration {
+        self.config.default_ttl
+    }
+
+    pub(crate) fn stores(&self, endpoint: Endpoint) -> bool {
+        match endpoint {
+            Endpoint::GetReadingLog | Endpoint::Login => false,
+            _ => self.conditional || !self.ttl(endpoint).is_zero(),
+        }
+    }
+
+    pub(crate) fn lookup(&self, key: &str) -> CacheLookup {
+        match self.config.store.get(key) {
+            Some(entry) if entry.is_fresh() => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                CacheLookup::Fresh(entry)
+            }
+            Some(entry) => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                CacheLookup::Stale(entry)
+            }
+            None => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                CacheLookup::Missing
+            }
+        }
+    }
+
+    pub(crate) fn put(&self, key: &str, entry: CacheEntry) {
+        self.config.store.put(key, entry);
+    }
+
+    /// Extends the lifetime of an entry the server confirmed is unchanged.
+    pub(crate) fn revalidated(&self, key: &str, entry: CacheEntry, ttl: Duration) {
+        self.revalidations.fetch_add(1, Ordering::Relaxed);
+        self.put(
+            key,
+            CacheEntry {
+                expires_at: SystemTime::now() + ttl,
+                ..entry
+            },
+        );
+    }
+
+    pub(crate) fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            revalidations: self.revalidations.load(Ordering::Relaxed),
+        }
+    }
+}
@@ -0,0 +1,348 @@
+use crate::transport::{HttpRequest, HttpResponse, HttpTransport};
+use crate::OpenLibraryError;
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use http::header::{HeaderName, HeaderValue, COOKIE, SET_COOKIE};
+use http::{HeaderMap, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use url::Url;
+
+const REDACTED: &str = "REDACTED";
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CassetteMode {
+    /// Send requests over the network and save every exchange to the cassette file.
+    Record,
+    /// Serve responses from the cassette file instead of the network.
+    Replay,
+}
+
+/// The parts of a request compared when looking for a recorded interaction to replay.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MatchOn {
+    Method,
+    Path,
+    Query,
+    Body,
+}
+
+/// Records the requests made by a client to a file so they can be replayed later without any
+/// network access. Request headers and the body of login requests are never written to the
+/// cassette, and the values of cookies set by responses are replaced with a placeholder, so
+/// credentials and session cookies don't end up checked in alongside tests.
+#[derive(Clone, Debug)]
+pub struct Cassette {
+    path: PathBuf,
+    mode: CassetteMode,
+    match_on: Vec<MatchOn>,
+    strict: bool,
+}
+
+impl Cassette {
+    pub fn record<P: Into<PathBuf>>(path: P) -> Self {
+        Self::new(path.into(), CassetteMode::Record)
+    }
+
+    pub fn replay<P: Into<PathBuf>>(path: P) -> Self {
+        Self::new(path.into(), CassetteMode::Replay)
+    }
+
+    fn new(path: PathBuf, mode: CassetteMode) -> Self {
+        Self {
+            path,
+            mode,
+            match_on: vec![
+                MatchOn::Method,
+                MatchOn::Path,
+                MatchOn::Query,
+                MatchOn::Body,
+            ],
+            strict: true,
+        }
+    }
+
+    pub fn with_match_on(self, match_on: Vec<MatchOn>) -> Self {
+        Self { match_on, ..self }
+    }
+
+    /// When replaying, fail requests with no recorded interaction instead of sending them over
+    /// the network. Enabled by default.
+    pub fn with_strict(self, strict: bool) -> Self {
+        Self { strict, ..self }
+    }
+
+    pub fn mode(&self) -> CassetteMode {
+        self.mode
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+struct CassetteFile {
+    interactions: Vec<Interaction>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct Interaction {
+    request: RecordedRequest,
+    response: RecordedResponse,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct RecordedRequest {
+    method: String,
+    url: Url,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct RecordedResponse {
+    status: u16,
+    #[serde(default)]
+    headers: Vec<(String, String)>,
+    url: Url,
+    body: String,
+}
+
+impl RecordedRequest {
+    fn from(request: &HttpRequest) -> Self {
+        Self {
+            method: request.method.to_string(),
+            url: request.url.clone(),
+            body: request.body.as_ref().map(|body| {
+                // Login requests carry the username and password
+                match request.url.path().ends_with("/account/login") {
+                    true => REDACTED.to_string(),
+                    false => String::from_utf8_lossy(body).to_string(),
+                }
+            }),
+        }
+    }
+
+    fn matches(&self, other: &RecordedRequest, match_on: &[MatchOn]) -> bool {
+        match_on.iter().all(|field| match field {
+            MatchOn::Method => self.method == other.method,
+            MatchOn::Path => self.url.path() == other.url.path(),
+            MatchOn::Query => {
+                let query = |url: &Url| {
+                    let mut pairs = url.query_pairs().into_owned().collect::<Vec<_>>();
+                    pairs.sort();
+                    pairs
+                };
+                query(&self.url) == query(&other.url)
+            }
+            MatchOn::Body => match (&self.body, &other.body) {
+                (Some(left), Some(right)) => {
+                    match (
+                        serde_json::from_str::<serde_json::Value>(left),
+                        serde_json::from_str::<serde_json::Value>(right),
+                    ) {
+                        (Ok(left), Ok(right)) => left == right,
+                        _ => left == right,
+                    }
+                }
+                (left, right) => left == right,
+            },
+        })
+    }
+}
+
+impl RecordedResponse {
+    fn from(response: &HttpResponse) -> Self {
+        Self {
+            status: response.status.as_u16(),
+            headers: response
+                .headers
+                .iter()
+                .filter(|(name, _)| *name != COOKIE)
+                .filter_map(|(name, value)| {
+                    let value = value.to_str().ok()?;
+                    Some(match *name == SET_COOKIE {
+                        true => (name.to_string(), redact_cookie(value)),
+                        false => (name.to_string(), value.to_string()),
+                    })
+                })
+                .collect(),
+            url: response.url.clone(),
+            body: String::from_utf8_lossy(&response.body).to_string(),
+        }
+    }
+
+    fn to_response(&self) -> Result<HttpResponse, OpenLibraryError> {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            headers.append(
+                HeaderName::from_bytes(name.as_bytes()).map_err(|error| {
+                    OpenLibraryError::CassetteError {
+                        reason: error.to_string(),
+                    }
+                })?,
+                HeaderValue::from_str(value).map_err(|error| OpenLibraryError::CassetteError {
+                    reason: error.to_string(),
+                })?,
+            );
+        }
+
+        Ok(HttpResponse {
+            status: StatusCode::from_u16(self.status).map_err(|error| {
+                OpenLibraryError::CassetteError {
+                    reason: error.to_string(),
+                }
+            })?,
+            headers,
+            url: self.url.clone(),
+            body: Bytes::from(self.body.clone()),
+        })
+    }
+}
+
+/// Replaces the value of a `Set-Cookie` header, keeping its name and attributes so replayed
+/// responses still set a cookie.
+fn redact_cookie(set_cookie: &str) -> String {
+    let (pair, attributes) = match set_cookie.find(';') {
+        Some(index) => set_cookie.split_at(index),
+        None => (set_cookie, ""),
+    };
+    let name = pair.split('=').next().unwrap_or_default().trim();
+
+    format!("{}={}{}", name, REDACTED, attributes)
+}
+
+struct CassetteState {
+    file: CassetteFile,
+    // Which recorded interactions have already been replayed, so repeated identical requests are
+    // answered with successive responses
+    used: Vec<bool>,
+}
+
+pub(crate) struct CassetteTransport {
+    cassette: Cassette,
+    inner: Arc<dyn HttpTransport>,
+    state: Mutex<CassetteState>,
+}
+
+impl CassetteTransport {
+    pub(crate) fn new(
+        cassette: Cassette,
+        inner: Arc<dyn HttpTransport>,
+    ) -> Result<Self, OpenLibraryError> {
+        let file = match cassette.mode {
+            CassetteMode::Record => CassetteFile::default(),
+            CassetteMode::Replay => {
+                let contents =
+                    fs::read(&cassette.path).map_err(|error| OpenLibraryError::CassetteError {
+                        reason: format!(
+                            "Unable to read cassette {}: {}",
+                            cassette.path.display(),
+                            error
+                        ),
+                    })?;
+                serde_json::from_slice(&contents).map_err(|error| {
+                    OpenLibraryError::CassetteError {
+                        reason: format!(
+                            "Unable to parse cassette {}: {}",
+                            cassette.path.display(),
+                            error
+                        ),
+                    }
+                })?
+            }
+        };
+
+        Ok(Self {
+            cassette,
+            inner,
+            state: Mutex::new(CassetteState {
+                used: vec![false; file.interactions.len()],
+                file,
+            }),
+        })
+    }
+
+    fn find(&self, request: &RecordedRequest) -> Option<RecordedResponse> {
+        let mut state = self.state.lock().unwrap_or_else(|error| error.into_inner());
+        let matching = state
+            .file
+            .interactions
+            .iter()
+            .enumerate()
+            .filter(|(_, interaction)| {
+                interaction
+                    .request
+                    .matches(request, &self.cassette.match_on)
+            })
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+
+        let index = matching
+            .iter()
+            .find(|index| !state.used[**index])
+            .or_else(|| matching.last())
+            .copied()?;
+        state.used[index] = true;
+
+        Some(state.file.interactions[index].response.clone())
+    }
+
+    fn save(&self, interaction: Interaction) -> Result<(), OpenLibraryError> {
+        let mut state = self.state.lock().unwrap_or_else(|error| error.into_inner());
+        state.file.interactions.push(interaction);
+
+        let contents = serde_json::to_vec_pretty(&state.file).map_err(|error| {
+            OpenLibraryError::CassetteError {
+                reason: format!(
+                    "Unable to encode cassette {}: {}",
+                    self.cassette.path.display(),
+                    error
+                ),
+            }
+        })?;
+        if let Some(parent) = self.cassette.path.parent() {
+            fs::create_dir_all(parent).map_err(|error| OpenLibraryError::CassetteError {
+                reason: error.to_string(),
+            })?;
+        }
+
+        fs::write(&self.cassette.path, contents).map_err(|error| OpenLibraryError::CassetteError {
+            reason: format!(
+                "Unable to write cassette {}: {}",
+                self.cassette.path.display(),
+                error
+            ),
+        })
+    }
+}
+
+impl HttpTransport for CassetteTransport {
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, OpenLibraryError>> {
+        async move {
+            let recorded = RecordedRequest::from(&request);
+
+            match self.cassette.mode {
+                CassetteMode::Record => {
+                    let response = self.inner.send(request).await?;
+                    self.save(Interaction {
+                        request: recorded,
+                        response: RecordedResponse::from(&response),
+                    })?;
+                    Ok(response)
+                }
+                CassetteMode::Replay => match self.find(&recorded) {
+                    Some(response) => response.to_response(),
+                    None if self.cassette.strict => Err(OpenLibraryError::CassetteError {
+                        reason: format!(
+                            "No recorded interaction matches {} {}",
+                            recorded.method, recorded.url
+                        ),
+                    }),
+                    None => self.inner.send(request).await,
+                },
+            }
+        }
+        .boxed()
+    }
+}
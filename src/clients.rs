@@ -1,8 +1,26 @@
-use crate::models::OpenLibraryModel;
-use crate::OpenLibraryError;
-use http::StatusCode;
-use reqwest::RequestBuilder;
+use crate::auth::Authenticator;
+use crate::cache::{CacheEntry, CacheLookup, CacheMode, CacheStats, ResponseCache};
+use crate::failover::{HostHealth, HostPool};
+use crate::interceptor::Interceptor;
+use crate::models::account::Session;
+use crate::models::{lenient, DeserializationMode, OpenLibraryModel, WithRaw};
+use crate::rate_limit::RateLimiter;
+use crate::retry::{self, RetryPolicy};
+use crate::telemetry;
+use crate::transport::{HttpRequest, HttpResponse, HttpTransport};
+use crate::{Endpoint, OpenLibraryError};
+use futures::future::{BoxFuture, Shared};
+use futures::FutureExt;
+use http::header::{COOKIE, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use http::{HeaderMap, HeaderValue, Method, StatusCode};
 use serde::Deserialize;
+use serde_json::Value;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use url::Url;
 
 pub mod account;
 pub mod author;
@@ -12,20 +30,485 @@ pub mod works;
 #[cfg(test)]
 mod tests;
 
-pub async fn handle<T>(request: RequestBuilder) -> Result<T, OpenLibraryError>
+#[derive(Clone)]
+pub struct HttpClient {
+    transport: Arc<dyn HttpTransport>,
+    default_headers: HeaderMap,
+    interceptors: Vec<Arc<dyn Interceptor>>,
+    retry_policy: RetryPolicy,
+    rate_limiter: RateLimiter,
+    cache: Option<ResponseCache>,
+    cache_mode: CacheMode,
+    coalescer: Option<Coalescer>,
+    timeout: Option<Duration>,
+    error_body_capture: Option<usize>,
+    deserialization_mode: DeserializationMode,
+    authenticator: Option<Authenticator>,
+    host_pool: Option<HostPool>,
+}
+
+type SharedResult =
+    Shared<BoxFuture<'static, Result<Arc<dyn Any + Send + Sync>, Arc<OpenLibraryError>>>>;
+
+/// Tracks GET requests currently in flight so identical ones can wait on the same result. Results
+/// are keyed by the type they're parsed into as well, since the same document may be requested as
+/// a model or as raw JSON.
+#[derive(Clone, Default)]
+pub(crate) struct Coalescer {
+    in_flight: Arc<Mutex<HashMap<(String, TypeId), SharedResult>>>,
+}
+
+impl Coalescer {
+    fn key(request: &HttpRequest, session: Option<&Session>) -> String {
+        // Headers are part of the key so e.g. a conditional request never shares an unconditional
+        // request's response, and so is the session, which is only added to the request later on,
+        // so clients logged in as different users, or not at all, never share responses
+        format!(
+            "{} {} {:?} {}",
+            request.method,
+            request.url,
+            request.headers,
+            session
+                .map(|session| session.cookie().as_str())
+                .unwrap_or_default()
+        )
+    }
+
+    fn remove(&self, key: &(String, TypeId)) {
+        self.in_flight
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+            .remove(key);
+    }
+}
+
+impl HttpClient {
+    pub fn new(transport: Arc<dyn HttpTransport>) -> Self {
+        Self {
+            transport,
+            default_headers: HeaderMap::new(),
+            interceptors: Vec::new(),
+            retry_policy: RetryPolicy::none(),
+            rate_limiter: RateLimiter::default(),
+            cache: None,
+            cache_mode: CacheMode::Default,
+            coalescer: None,
+            timeout: None,
+            error_body_capture: None,
+            deserialization_mode: DeserializationMode::Strict,
+            authenticator: None,
+            host_pool: None,
+        }
+    }
+
+    pub(crate) fn with_default_headers(self, default_headers: HeaderMap) -> Self {
+        Self {
+            default_headers,
+            ..self
+        }
+    }
+
+    pub(crate) fn with_interceptors(self, interceptors: Vec<Arc<dyn Interceptor>>) -> Self {
+        Self {
+            interceptors,
+            ..self
+        }
+    }
+
+    pub(crate) fn with_retry_policy(self, retry_policy: RetryPolicy) -> Self {
+        Self {
+            retry_policy,
+            ..self
+        }
+    }
+
+    pub(crate) fn with_rate_limiter(self, rate_limiter: RateLimiter) -> Self {
+        Self {
+            rate_limiter,
+            ..self
+        }
+    }
+
+    pub(crate) fn with_cache(self, cache: Option<ResponseCache>) -> Self {
+        Self { cache, ..self }
+    }
+
+    pub(crate) fn with_cache_mode(self, cache_mode: CacheMode) -> Self {
+        Self { cache_mode, ..self }
+    }
+
+    pub(crate) fn with_coalescer(self, coalescer: Option<Coalescer>) -> Self {
+        Self { coalescer, ..self }
+    }
+
+    pub(crate) fn with_timeout(self, timeout: Option<Duration>) -> Self {
+        Self { timeout, ..self }
+    }
+
+    pub(crate) fn with_error_body_capture(self, error_body_capture: Option<usize>) -> Self {
+        Self {
+            error_body_capture,
+            ..self
+        }
+    }
+
+    pub(crate) fn with_deserialization_mode(
+        self,
+        deserialization_mode: DeserializationMode,
+    ) -> Self {
+        Self {
+            deserialization_mode,
+            ..self
+        }
+    }
+
+    pub(crate) fn with_authenticator(self, authenticator: Option<Authenticator>) -> Self {
+        Self {
+            authenticator,
+            ..self
+        }
+    }
+
+    /// A client with this client's configuration which sends the given session, and logs in again
+    /// with this client's credentials, without affecting this client.
+    pub(crate) fn with_session(self, session: Session, host: &Url) -> Self {
+        let authenticator = match &self.authenticator {
+            Some(authenticator) => authenticator.with_session(session),
+            None => Authenticator::new(Some(session), None, host.clone()),
+        };
+
+        self.with_authenticator(Some(authenticator))
+    }
+
+    pub(crate) fn session(&self) -> Option<Session> {
+        self.authenticator.as_ref().and_then(Authenticator::session)
+    }
+
+    /// Forgets the session for this client and every client sharing it.
+    pub(crate) fn clear_session(&self) {
+        if let Some(authenticator) = &self.authenticator {
+            authenticator.clear_session();
+        }
+    }
+
+    pub(crate) fn can_authenticate(&self) -> bool {
+        self.authenticator.is_some()
+    }
+
+    pub(crate) async fn require_session(&self) -> Result<Session, OpenLibraryError> {
+        match &self.authenticator {
+            Some(authenticator) => authenticator.require_session(self).await,
+            None => Err(OpenLibraryError::NotAuthenticated {
+                reason: "The client has no session and no credentials to log in with".to_string(),
+            }),
+        }
+    }
+
+    pub(crate) fn with_host_pool(self, host_pool: Option<HostPool>) -> Self {
+        Self { host_pool, ..self }
+    }
+
+    pub(crate) fn host_health(&self) -> Option<Vec<HostHealth>> {
+        self.host_pool.as_ref().map(HostPool::health)
+    }
+
+    pub(crate) fn cache_stats(&self) -> Option<CacheStats> {
+        self.cache.as_ref().map(ResponseCache::stats)
+    }
+
+    pub fn get(&self, url: Url) -> HttpRequest {
+        HttpRequest::get(url)
+    }
+
+    pub fn post(&self, url: Url) -> HttpRequest {
+        HttpRequest::post(url)
+    }
+
+    pub async fn send(&self, mut request: HttpRequest) -> Result<HttpResponse, OpenLibraryError> {
+        if request.timeout.is_none() {
+            request.timeout = self.timeout;
+        }
+
+        let authenticator = match &self.authenticator {
+            Some(authenticator) if authenticator.can_refresh() => authenticator,
+            _ => return self.execute(request).await,
+        };
+
+        let session = authenticator.session();
+        let response = self.execute(request.clone()).await?;
+        if !authenticator.is_rejected(&response) {
+            return Ok(response);
+        }
+
+        // Replay the request once with a fresh session, returning whatever it receives
+        authenticator.refresh(self, session).await?;
+        self.execute(request).await
+    }
+
+    /// Runs `fetch` for the request, unless an identical GET request is already being fetched into
+    /// the same type, in which case its result is shared instead.
+    pub(crate) async fn coalesce<T, F>(
+        &self,
+        request: &HttpRequest,
+        fetch: F,
+    ) -> Result<T, OpenLibraryError>
+    where
+        T: Clone + Send + Sync + 'static,
+        F: Future<Output = Result<T, OpenLibraryError>> + Send + 'static,
+    {
+        let coalescer = match &self.coalescer {
+            Some(coalescer) if request.method == Method::GET => coalescer.clone(),
+            _ => return fetch.await,
+        };
+
+        let key = (
+            Coalescer::key(request, self.session().as_ref()),
+            TypeId::of::<T>(),
+        );
+        let result = coalescer
+            .in_flight
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+            .entry(key.clone())
+            .or_insert_with(|| {
+                let coalescer = coalescer.clone();
+                async move {
+                    let result = fetch
+                        .await
+                        .map(|value| Arc::new(value) as Arc<dyn Any + Send + Sync>)
+                        .map_err(Arc::new);
+                    coalescer.remove(&key);
+                    result
+                }
+                .boxed()
+                .shared()
+            })
+            .clone();
+
+        // A failure only reaches a caller as is when no other caller shared it
+        match result.await {
+            Ok(value) => Ok(value
+                .downcast_ref::<T>()
+                .cloned()
+                .expect("Coalesced results are keyed by their type")),
+            Err(error) => Err(Arc::try_unwrap(error)
+                .unwrap_or_else(|source| OpenLibraryError::CoalescedRequestFailed { source })),
+        }
+    }
+
+    async fn execute(&self, mut request: HttpRequest) -> Result<HttpResponse, OpenLibraryError> {
+        for name in self.default_headers.keys() {
+            if !request.headers.contains_key(name) {
+                for value in self.default_headers.get_all(name) {
+                    request.headers.append(name, value.clone());
+                }
+            }
+        }
+
+        if let Some(session) = self.session() {
+            let cookie = match request.headers.get(COOKIE).map(|value| value.to_str()) {
+                Some(Ok(existing)) => format!("{}; {}", existing, session.cookie()),
+                _ => session.cookie().to_string(),
+            };
+
+            request.headers.insert(
+                COOKIE,
+                HeaderValue::from_str(cookie.as_str()).map_err(|_error| {
+                    OpenLibraryError::ParsingError {
+                        reason: "Unable to parse session cookie into header value".to_string(),
+                    }
+                })?,
+            );
+        }
+
+        for (index, interceptor) in self.interceptors.iter().enumerate() {
+            if let Some(response) = interceptor.on_request(&mut request)? {
+                return self.intercept_response(&request, response, index);
+            }
+        }
+
+        let request = &request;
+        retry::send_with_retries(&self.retry_policy, &request.method, |attempt| async move {
+            telemetry::record_attempt(attempt);
+            self.rate_limiter.acquire(&request.url).await?;
+            let response = self.transport.send(request.clone()).await?;
+            let response = self.intercept_response(request, response, self.interceptors.len())?;
+            telemetry::record_status(response.status);
+            Ok(response)
+        })
+        .await
+    }
+
+    pub(crate) fn parse<T>(&self, url: &Url, body: &[u8]) -> Result<T, OpenLibraryError>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let _mode = lenient::with_mode(self.deserialization_mode);
+        let mut deserializer = serde_json::Deserializer::from_slice(body);
+        let mut track = serde_path_to_error::Track::new();
+        T::deserialize(serde_path_to_error::Deserializer::new(
+            &mut deserializer,
+            &mut track,
+        ))
+        .and_then(|value| deserializer.end().map(|_| value))
+        .map_err(|error| serde_path_to_error::Error::new(track.path(), error))
+        .map_err(|error| OpenLibraryError::DeserializationError {
+            url: url.clone(),
+            source: Box::new(error),
+            body: self.error_body_capture.map(|max_bytes| {
+                String::from_utf8_lossy(&body[..body.len().min(max_bytes)]).into_owned()
+            }),
+        })
+    }
+
+    /// Parses the model out of an already fetched raw document, keeping the document alongside it.
+    pub(crate) fn parse_raw<T>(&self, url: &Url, raw: Value) -> Result<WithRaw<T>, OpenLibraryError>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        // Parsed from the document's bytes so failures still report the path of the bad field
+        let body = serde_json::to_vec(&raw)
+            .map_err(|error| OpenLibraryError::JsonParseError { source: error })?;
+
+        Ok(WithRaw {
+            model: self.parse(url, &body)?,
+            raw,
+        })
+    }
+
+    // Only the interceptors which saw the request get to see its response
+    fn intercept_response(
+        &self,
+        request: &HttpRequest,
+        mut response: HttpResponse,
+        count: usize,
+    ) -> Result<HttpResponse, OpenLibraryError> {
+        for interceptor in self.interceptors[..count].iter().rev() {
+            interceptor.on_response(request, &mut response)?;
+        }
+
+        Ok(response)
+    }
+}
+
+pub async fn handle<T>(
+    client: &HttpClient,
+    endpoint: Endpoint,
+    request: HttpRequest,
+) -> Result<T, OpenLibraryError>
+where
+    T: for<'de> Deserialize<'de> + OpenLibraryModel + Clone + Send + Sync + 'static,
+{
+    let url = request.url.clone();
+    let fetched = {
+        let (client, request) = (client.clone(), request.clone());
+        async move { fetch(&client, endpoint, request).await }
+    };
+
+    telemetry::observe(endpoint, &url, client.coalesce(&request, fetched)).await
+}
+
+pub async fn handle_with_raw<T>(
+    client: &HttpClient,
+    endpoint: Endpoint,
+    request: HttpRequest,
+) -> Result<WithRaw<T>, OpenLibraryError>
+where
+    T: for<'de> Deserialize<'de> + OpenLibraryModel + Clone + Send + Sync + 'static,
+{
+    let url = request.url.clone();
+    let fetched = {
+        let (client, request, url) = (client.clone(), request.clone(), url.clone());
+        async move {
+            let raw: Value = fetch(&client, endpoint, request).await?;
+            client.parse_raw(&url, raw)
+        }
+    };
+
+    telemetry::observe(endpoint, &url, client.coalesce(&request, fetched)).await
+}
+
+async fn fetch<T>(
+    client: &HttpClient,
+    endpoint: Endpoint,
+    mut request: HttpRequest,
+) -> Result<T, OpenLibraryError>
 where
     T: for<'de> Deserialize<'de> + OpenLibraryModel,
 {
-    let response = request.send().await?;
-
-    return match response.status() {
-        StatusCode::OK => Ok(response
-            .json::<T>()
-            .await
-            .map_err(|error| OpenLibraryError::JsonParseError { source: error })?),
-        _ => Err(OpenLibraryError::ApiError {
-            status_code: response.status(),
-            error: None,
-        }),
+    let cache = match (&client.cache, client.cache_mode) {
+        (_, CacheMode::Bypass) => None,
+        (Some(cache), _) if request.method == Method::GET && cache.stores(endpoint) => {
+            Some((cache, request.url.to_string(), cache.ttl(endpoint)))
+        }
+        _ => None,
     };
+
+    let url = request.url.clone();
+    let mut stale: Option<CacheEntry> = None;
+    if let (Some((cache, key, _)), CacheMode::Default) = (&cache, client.cache_mode) {
+        let lookup = cache.lookup(key);
+        telemetry::record_cache_hit(matches!(lookup, CacheLookup::Fresh(_)));
+        match lookup {
+            CacheLookup::Fresh(entry) => return client.parse(&url, &entry.body),
+            CacheLookup::Stale(entry) if cache.is_conditional() => {
+                let headers = &mut request.headers;
+                if let Some(value) = entry.etag.as_deref().and_then(header_value) {
+                    headers.insert(IF_NONE_MATCH, value);
+                }
+                if let Some(value) = entry.last_modified.as_deref().and_then(header_value) {
+                    headers.insert(IF_MODIFIED_SINCE, value);
+                }
+                stale = Some(entry);
+            }
+            _ => {}
+        }
+    }
+
+    let response = client.send(request).await?;
+
+    match (response.status, stale) {
+        (StatusCode::OK, _) => {
+            let header = |name| {
+                response
+                    .headers
+                    .get(name)
+                    .and_then(|value: &HeaderValue| value.to_str().ok())
+                    .map(str::to_string)
+            };
+            let etag = header(ETAG);
+            let last_modified = header(LAST_MODIFIED);
+
+            let body = response.body;
+            let value = client.parse(&response.url, &body)?;
+            if let Some((cache, key, ttl)) = cache {
+                cache.put(
+                    key.as_str(),
+                    CacheEntry {
+                        body: body.to_vec(),
+                        expires_at: SystemTime::now() + ttl,
+                        etag,
+                        last_modified,
+                        headers: response.headers,
+                        url: Some(response.url),
+                    },
+                );
+            }
+
+            Ok(value)
+        }
+        (StatusCode::NOT_MODIFIED, Some(entry)) => {
+            let value = client.parse(&url, &entry.body)?;
+            if let Some((cache, key, ttl)) = cache {
+                cache.revalidated(key.as_str(), entry, ttl);
+            }
+
+            Ok(value)
+        }
+        _ => Err(OpenLibraryError::from_response(&response)),
+    }
+}
+
+fn header_value(value: &str) -> Option<HeaderValue> {
+    HeaderValue::from_str(value).ok()
 }
@@ -1,19 +1,51 @@
-use crate::models::account::{LoginRequest, ReadingLogEntry, ReadingLogResponseWrapper, Session};
-use crate::OpenLibraryError;
-use reqwest::{Client, StatusCode};
+use crate::auth::{Anonymous, Authenticated, LoginFailure};
+use crate::clients::HttpClient;
+use crate::models::account::{
+    BookshelfRequest, ListResponse, ListSeedsRequest, LoginRequest, NewList, RatingRequest,
+    ReadingLog, ReadingLogEntry, ReadingLogResponse, Session,
+};
+use crate::models::identifiers::{Identifier, OpenLibraryIdentifier};
+use crate::models::{OpenLibraryResource, WithRaw};
+use crate::pagination::{Page, PageCursor, Paginated};
+use crate::telemetry;
+use crate::transport::{HttpRequest, HttpResponse};
+use crate::{Endpoint, OpenLibraryError, OpenLibraryErrorResponse};
+use futures::FutureExt;
+use http::header::{COOKIE, LOCATION, SET_COOKIE};
+use http::{HeaderValue, StatusCode};
+use serde::Deserialize;
+use serde_json::Value;
+use std::marker::PhantomData;
+use std::str::FromStr;
 use url::Url;
 
-#[derive(Clone)]
-pub struct AccountClient {
-    pub client: Client,
-    pub host: Url,
+const DEFAULT_READING_LOG_PAGE_SIZE: u32 = 100;
+
+/// Account operations. Changes to the account are only available once the client is
+/// [`Authenticated`].
+pub struct AccountClient<A = Anonymous> {
+    client: HttpClient,
+    host: Url,
+    // Only marks the state, so the client is `Send` and `Sync` whatever the marker
+    state: PhantomData<fn() -> A>,
+}
+
+impl<A> Clone for AccountClient<A> {
+    fn clone(&self) -> Self {
+        Self {
+            client: self.client.clone(),
+            host: self.host.clone(),
+            state: PhantomData,
+        }
+    }
 }
 
-impl AccountClient {
-    pub fn new(client: &Client, host: &Url) -> Self {
+impl<A> AccountClient<A> {
+    pub fn new(client: &HttpClient, host: &Url) -> Self {
         Self {
             client: client.clone(),
             host: host.clone(),
+            state: PhantomData,
         }
     }
 
@@ -22,7 +54,7 @@ impl AccountClient {
         username: String,
         password: String,
     ) -> Result<Session, OpenLibraryError> {
-        let response = self
+        let request = self
             .client
             .post(self.host.join("/account/login").map_err(|error| {
                 OpenLibraryError::InternalError {
@@ -32,92 +64,523 @@ impl AccountClient {
             .json(&LoginRequest {
                 username: username.clone(),
                 password: password.clone(),
-            })
-            .send()
-            .await?;
+            })?;
+        let url = request.url.clone();
 
-        match response.status() {
-            StatusCode::OK => {
-                let cookie = response
-                    .headers()
-                    .get(http::header::SET_COOKIE)
-                    .ok_or(OpenLibraryError::ParsingError {
-                        reason:
-                            "The API response from Open Library did not include a Set-Cookie header"
+        // Logging in replaces any session rather than sending it, and a failed login is no reason
+        // to log in with the configured credentials instead
+        let client = self.client.clone().with_authenticator(None);
+
+        telemetry::observe(Endpoint::Login, &url, async move {
+            let response = client.send(request).await?;
+            let error = serde_json::from_slice::<OpenLibraryErrorResponse>(&response.body).ok();
+
+            match (response.status, response.headers.get(SET_COOKIE), error) {
+                (StatusCode::OK, Some(cookie), _) => {
+                    let cookie = cookie
+                        .to_str()
+                        .map_err(|_e| OpenLibraryError::ParsingError {
+                            reason: "Unable to parse Set-Cookie Header Value into String"
                                 .to_string(),
-                    })?
-                    .to_str()
-                    .map_err(|_e| OpenLibraryError::ParsingError {
-                        reason: "Unable to parse Set-Cookie Header Value into String".to_string(),
-                    })?
-                    .to_string();
-
-                Ok(Session::from(cookie, username))
+                        })?;
+
+                    Ok(Session::from(cookie.to_string(), username))
+                }
+                (StatusCode::OK, None, None) => Err(OpenLibraryError::LoginFailed {
+                    reason: LoginFailure::MissingSession,
+                    status_code: response.status,
+                    error: None,
+                }),
+                // Rate limiting and server errors keep their own variants so they can be retried
+                (status_code, _, Some(error))
+                    if status_code == StatusCode::OK
+                        || (status_code.is_client_error()
+                            && status_code != StatusCode::TOO_MANY_REQUESTS) =>
+                {
+                    Err(OpenLibraryError::LoginFailed {
+                        reason: LoginFailure::from_code(error.error.as_str()),
+                        status_code,
+                        error: Some(error),
+                    })
+                }
+                _ => Err(OpenLibraryError::from_response(&response)),
             }
-            _ => Err(OpenLibraryError::ApiError {
-                status_code: response.status(),
-                error: None,
-            }),
-        }
+        })
+        .await
+    }
+
+    /// The username the client's session belongs to, failing with `NotAuthenticated` when the
+    /// client has no session or Open Library no longer accepts it.
+    pub async fn whoami(&self) -> Result<String, OpenLibraryError> {
+        let url = self.host.join("/account")?;
+        let request = self.client.get(url.clone());
+
+        telemetry::observe(Endpoint::WhoAmI, &url, async move {
+            let response = self.client.send(request).await?;
+
+            // Open Library redirects the account page to the logged in user's profile, and to the
+            // login page without a valid session
+            let location = response
+                .headers
+                .get(LOCATION)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|location| response.url.join(location).ok());
+            let profile = location
+                .iter()
+                .chain(std::iter::once(&response.url))
+                .find_map(|url| {
+                    let mut segments = url.path_segments()?;
+                    match (segments.next(), segments.next()) {
+                        (Some("people"), Some(username)) if !username.is_empty() => {
+                            Some(username.trim_end_matches(".json").to_string())
+                        }
+                        _ => None,
+                    }
+                });
+
+            match profile {
+                Some(username) => Ok(username),
+                None if response.status.is_success() || response.status.is_redirection() => {
+                    Err(OpenLibraryError::NotAuthenticated {
+                        reason: "Open Library did not recognise the session".to_string(),
+                    })
+                }
+                None => Err(OpenLibraryError::from_response(&response)),
+            }
+        })
+        .await
     }
 
     pub async fn get_already_read(
         &self,
         username: String,
     ) -> Result<Vec<ReadingLogEntry>, OpenLibraryError> {
-        let want_to_read_url = self
-            .host
-            .join(format!("/people/{}/books/already-read.json", username).as_str())
-            .map_err(|_e| OpenLibraryError::ParsingError {
-                reason: "Unable to parse into valid URL".to_string(),
-            })?;
+        self.get_reading_log(self.reading_log_url(ReadingLog::AlreadyRead, &username)?)
+            .await
+    }
 
-        self.get_reading_log(want_to_read_url).await
+    pub async fn get_already_read_raw(&self, username: String) -> Result<Value, OpenLibraryError> {
+        self.fetch_reading_log(
+            self.client
+                .get(self.reading_log_url(ReadingLog::AlreadyRead, &username)?),
+        )
+        .await
+    }
+
+    pub async fn get_already_read_with_raw(
+        &self,
+        username: String,
+    ) -> Result<WithRaw<Vec<ReadingLogEntry>>, OpenLibraryError> {
+        self.get_reading_log_with_raw(self.reading_log_url(ReadingLog::AlreadyRead, &username)?)
+            .await
     }
 
     pub async fn get_currently_reading(
         &self,
         username: String,
     ) -> Result<Vec<ReadingLogEntry>, OpenLibraryError> {
-        let want_to_read_url = self
-            .host
-            .join(format!("/people/{}/books/currently-reading.json", username).as_str())
-            .map_err(|_e| OpenLibraryError::ParsingError {
-                reason: "Unable to parse into valid URL".to_string(),
-            })?;
+        self.get_reading_log(self.reading_log_url(ReadingLog::CurrentlyReading, &username)?)
+            .await
+    }
+
+    pub async fn get_currently_reading_raw(
+        &self,
+        username: String,
+    ) -> Result<Value, OpenLibraryError> {
+        self.fetch_reading_log(
+            self.client
+                .get(self.reading_log_url(ReadingLog::CurrentlyReading, &username)?),
+        )
+        .await
+    }
 
-        self.get_reading_log(want_to_read_url).await
+    pub async fn get_currently_reading_with_raw(
+        &self,
+        username: String,
+    ) -> Result<WithRaw<Vec<ReadingLogEntry>>, OpenLibraryError> {
+        self.get_reading_log_with_raw(
+            self.reading_log_url(ReadingLog::CurrentlyReading, &username)?,
+        )
+        .await
     }
 
     pub async fn get_want_to_read(
         &self,
         username: String,
     ) -> Result<Vec<ReadingLogEntry>, OpenLibraryError> {
-        let want_to_read_url = self
-            .host
-            .join(format!("/people/{}/books/want-to-read.json", username).as_str())
+        self.get_reading_log(self.reading_log_url(ReadingLog::WantToRead, &username)?)
+            .await
+    }
+
+    pub async fn get_want_to_read_raw(&self, username: String) -> Result<Value, OpenLibraryError> {
+        self.fetch_reading_log(
+            self.client
+                .get(self.reading_log_url(ReadingLog::WantToRead, &username)?),
+        )
+        .await
+    }
+
+    pub async fn get_want_to_read_with_raw(
+        &self,
+        username: String,
+    ) -> Result<WithRaw<Vec<ReadingLogEntry>>, OpenLibraryError> {
+        self.get_reading_log_with_raw(self.reading_log_url(ReadingLog::WantToRead, &username)?)
+            .await
+    }
+
+    pub fn get_reading_log_paginated(
+        &self,
+        reading_log: ReadingLog,
+        username: String,
+    ) -> Result<Paginated<ReadingLogEntry>, OpenLibraryError> {
+        let url = self.reading_log_url(reading_log, &username)?;
+        // Reading the log doesn't depend on the state, which needn't outlive the stream
+        let client = AccountClient::<Anonymous>::new(&self.client, &self.host);
+
+        Ok(Paginated::new(
+            PageCursor::Offset(0),
+            DEFAULT_READING_LOG_PAGE_SIZE,
+            move |cursor, page_size| {
+                let client = client.clone();
+                let url = url.clone();
+                async move {
+                    let url = match cursor {
+                        PageCursor::Link(url) => url,
+                        PageCursor::Offset(offset) => {
+                            let mut url = with_page(&url, offset / page_size.max(1) + 1);
+                            url.query_pairs_mut()
+                                .append_pair("limit", page_size.to_string().as_str());
+                            url
+                        }
+                    };
+                    let page = url
+                        .query_pairs()
+                        .find(|(name, _)| name == "page")
+                        .and_then(|(_, value)| value.parse::<u32>().ok())
+                        .unwrap_or(1);
+
+                    let response = client
+                        .fetch_reading_log::<ReadingLogResponse>(client.client.get(url.clone()))
+                        .await?;
+                    // The server may return fewer or more entries than asked for, so carry on
+                    // from the page actually fetched until one comes back empty
+                    let next = match response.reading_log_entries.is_empty() {
+                        true => None,
+                        false => Some(PageCursor::Link(with_page(&url, page + 1))),
+                    };
+
+                    Ok(Page {
+                        items: response.reading_log_entries,
+                        next,
+                    })
+                }
+                .boxed()
+            },
+        ))
+    }
+
+    fn reading_log_url(
+        &self,
+        reading_log: ReadingLog,
+        username: &str,
+    ) -> Result<Url, OpenLibraryError> {
+        self.host
+            .join(format!("/people/{}/books/{}", username, reading_log.url()).as_str())
             .map_err(|_e| OpenLibraryError::ParsingError {
                 reason: "Unable to parse into valid URL".to_string(),
-            })?;
-
-        self.get_reading_log(want_to_read_url).await
+            })
     }
 
     async fn get_reading_log(&self, url: Url) -> Result<Vec<ReadingLogEntry>, OpenLibraryError> {
-        let response = self.client.get(url).send().await?;
+        Ok(self
+            .fetch_reading_log::<ReadingLogResponse>(self.client.get(url))
+            .await?
+            .reading_log_entries)
+    }
 
-        let status_code = response.status();
-        let reading_log_response = response
-            .json::<ReadingLogResponseWrapper>()
-            .await
-            .map_err(|error| OpenLibraryError::JsonParseError { source: error })?;
-
-        match reading_log_response {
-            ReadingLogResponseWrapper::Success(value) => Ok(value.reading_log_entries),
-            ReadingLogResponseWrapper::Err(error) => Err(OpenLibraryError::ApiError {
-                status_code,
-                error: Some(error),
-            }),
+    async fn get_reading_log_with_raw(
+        &self,
+        url: Url,
+    ) -> Result<WithRaw<Vec<ReadingLogEntry>>, OpenLibraryError> {
+        let raw: Value = self.fetch_reading_log(self.client.get(url.clone())).await?;
+        let response: WithRaw<ReadingLogResponse> = self.client.parse_raw(&url, raw)?;
+
+        Ok(WithRaw {
+            model: response.model.reading_log_entries,
+            raw: response.raw,
+        })
+    }
+
+    async fn fetch_reading_log<T>(&self, request: HttpRequest) -> Result<T, OpenLibraryError>
+    where
+        T: for<'de> Deserialize<'de> + Clone + Send + Sync + 'static,
+    {
+        let url = request.url.clone();
+        let fetched = {
+            let (client, request) = (self.client.clone(), request.clone());
+            async move {
+                let response = client.send(request).await?;
+                if response.status != StatusCode::OK {
+                    return Err(OpenLibraryError::from_response(&response));
+                }
+
+                // Open Library reports some failures, e.g. private shelves, in a 200 response
+                match serde_json::from_slice::<OpenLibraryErrorResponse>(&response.body) {
+                    Ok(error) => Err(OpenLibraryError::ApiError {
+                        url: response.url,
+                        status_code: response.status,
+                        error: Some(error),
+                    }),
+                    Err(_) => client.parse(&response.url, &response.body),
+                }
+            }
+        };
+
+        telemetry::observe(
+            Endpoint::GetReadingLog,
+            &url,
+            self.client.coalesce(&request, fetched),
+        )
+        .await
+    }
+}
+
+impl AccountClient<Authenticated> {
+    /// Invalidates the session with Open Library and forgets it, for this client and every client
+    /// sharing the session.
+    pub async fn logout(&self) -> Result<(), OpenLibraryError> {
+        let session = match self.client.session() {
+            Some(session) => session,
+            None => return Ok(()),
+        };
+        let url = self.host.join("/account/logout")?;
+        let request = self.client.post(url.clone()).header(
+            COOKIE,
+            HeaderValue::from_str(session.cookie()).map_err(|_error| {
+                OpenLibraryError::ParsingError {
+                    reason: "Unable to parse session cookie into header value".to_string(),
+                }
+            })?,
+        );
+
+        // Sent without the authenticator so a rejected session doesn't lead to logging in again
+        let client = self.client.clone().with_authenticator(None);
+        telemetry::observe(Endpoint::Logout, &url, async move {
+            let response = client.send(request).await?;
+            match response.status.is_success() || response.status.is_redirection() {
+                true => Ok(()),
+                false => Err(OpenLibraryError::from_response(&response)),
+            }
+        })
+        .await?;
+
+        self.client.clear_session();
+        Ok(())
+    }
+
+    pub async fn add_to_reading_log(
+        &self,
+        work: &OpenLibraryIdentifier,
+        reading_log: ReadingLog,
+    ) -> Result<(), OpenLibraryError> {
+        self.edit_bookshelf(work, "add", reading_log).await
+    }
+
+    pub async fn remove_from_reading_log(
+        &self,
+        work: &OpenLibraryIdentifier,
+        reading_log: ReadingLog,
+    ) -> Result<(), OpenLibraryError> {
+        self.edit_bookshelf(work, "remove", reading_log).await
+    }
+
+    /// Rates the work from 1 to 5 stars, replacing any previous rating.
+    pub async fn rate(
+        &self,
+        work: &OpenLibraryIdentifier,
+        rating: u8,
+    ) -> Result<(), OpenLibraryError> {
+        if !(1..=5).contains(&rating) {
+            return Err(OpenLibraryError::ParsingError {
+                reason: format!("Ratings must be from 1 to 5 but {} was given", rating),
+            });
         }
+
+        self.edit_rating(
+            work,
+            RatingRequest {
+                rating: Some(rating),
+                action: None,
+                redir: false,
+            },
+        )
+        .await
+    }
+
+    pub async fn remove_rating(
+        &self,
+        work: &OpenLibraryIdentifier,
+    ) -> Result<(), OpenLibraryError> {
+        self.edit_rating(
+            work,
+            RatingRequest {
+                rating: None,
+                action: Some("delete"),
+                redir: false,
+            },
+        )
+        .await
+    }
+
+    /// Creates a list owned by the logged in user, returning its identifier.
+    pub async fn create_list(
+        &self,
+        list: &NewList,
+    ) -> Result<OpenLibraryIdentifier, OpenLibraryError> {
+        let session = self.client.require_session().await?;
+        let url = self.lists_url(&session, "lists.json")?;
+
+        let response = self
+            .submit(Endpoint::CreateList, self.client.post(url).json(list)?)
+            .await?;
+        let created: ListResponse = self.client.parse(&response.url, &response.body)?;
+
+        created
+            .key
+            .rsplit('/')
+            .find(|segment| !segment.is_empty())
+            .ok_or_else(|| OpenLibraryError::ParsingError {
+                reason: format!("Unable to find the list identifier in {}", created.key),
+            })
+            .and_then(OpenLibraryIdentifier::from_str)
+    }
+
+    /// Adds works, editions or authors to one of the logged in user's lists.
+    pub async fn add_to_list(
+        &self,
+        list: &OpenLibraryIdentifier,
+        seeds: &[OpenLibraryResource],
+    ) -> Result<(), OpenLibraryError> {
+        self.edit_list(list, seeds, &[]).await
     }
+
+    pub async fn remove_from_list(
+        &self,
+        list: &OpenLibraryIdentifier,
+        seeds: &[OpenLibraryResource],
+    ) -> Result<(), OpenLibraryError> {
+        self.edit_list(list, &[], seeds).await
+    }
+
+    pub async fn delete_list(&self, list: &OpenLibraryIdentifier) -> Result<(), OpenLibraryError> {
+        let session = self.client.require_session().await?;
+        let url = self.lists_url(
+            &session,
+            format!("lists/{}/delete.json", list.value()).as_str(),
+        )?;
+
+        self.submit(Endpoint::DeleteList, self.client.post(url))
+            .await
+            .map(|_| ())
+    }
+
+    async fn edit_bookshelf(
+        &self,
+        work: &OpenLibraryIdentifier,
+        action: &'static str,
+        reading_log: ReadingLog,
+    ) -> Result<(), OpenLibraryError> {
+        self.client.require_session().await?;
+        let url = self
+            .host
+            .join(format!("/works/{}/bookshelves.json", work.value()).as_str())?;
+        let request = self.client.post(url).form(&BookshelfRequest {
+            action,
+            bookshelf_id: reading_log.bookshelf_id(),
+            redir: false,
+        })?;
+
+        self.submit(Endpoint::UpdateReadingLog, request)
+            .await
+            .map(|_| ())
+    }
+
+    async fn edit_rating(
+        &self,
+        work: &OpenLibraryIdentifier,
+        rating: RatingRequest,
+    ) -> Result<(), OpenLibraryError> {
+        self.client.require_session().await?;
+        let url = self
+            .host
+            .join(format!("/works/{}/ratings.json", work.value()).as_str())?;
+
+        self.submit(Endpoint::RateWork, self.client.post(url).form(&rating)?)
+            .await
+            .map(|_| ())
+    }
+
+    async fn edit_list(
+        &self,
+        list: &OpenLibraryIdentifier,
+        add: &[OpenLibraryResource],
+        remove: &[OpenLibraryResource],
+    ) -> Result<(), OpenLibraryError> {
+        let session = self.client.require_session().await?;
+        let url = self.lists_url(
+            &session,
+            format!("lists/{}/seeds.json", list.value()).as_str(),
+        )?;
+        let request = self
+            .client
+            .post(url)
+            .json(&ListSeedsRequest { add, remove })?;
+
+        self.submit(Endpoint::UpdateList, request).await.map(|_| ())
+    }
+
+    fn lists_url(&self, session: &Session, path: &str) -> Result<Url, OpenLibraryError> {
+        Ok(self
+            .host
+            .join(format!("/people/{}/{}", session.username(), path).as_str())?)
+    }
+
+    async fn submit(
+        &self,
+        endpoint: Endpoint,
+        request: HttpRequest,
+    ) -> Result<HttpResponse, OpenLibraryError> {
+        let url = request.url.clone();
+
+        telemetry::observe(endpoint, &url, async move {
+            let response = self.client.send(request).await?;
+            if response.status != StatusCode::OK {
+                return Err(OpenLibraryError::from_response(&response));
+            }
+
+            match serde_json::from_slice::<OpenLibraryErrorResponse>(&response.body) {
+                Ok(error) => Err(OpenLibraryError::ApiError {
+                    url: response.url,
+                    status_code: response.status,
+                    error: Some(error),
+                }),
+                Err(_) => Ok(response),
+            }
+        })
+        .await
+    }
+}
+
+/// Points a reading log URL at the given page, keeping its other query parameters.
+fn with_page(url: &Url, page: u32) -> Url {
+    let mut parameters = url
+        .query_pairs()
+        .filter(|(name, _)| name != "page")
+        .map(|(name, value)| (name.into_owned(), value.into_owned()))
+        .collect::<Vec<_>>();
+    parameters.insert(0, ("page".to_string(), page.to_string()));
+
+    let mut url = url.clone();
+    url.query_pairs_mut().clear().extend_pairs(parameters);
+    url
 }
@@ -1,22 +1,32 @@
-use crate::clients::handle;
+use crate::batch::{fetch_many, BatchOptions, BatchResponse};
+use crate::clients::HttpClient;
+use crate::clients::{handle, handle_with_raw};
 use crate::models::authors::{
-    AuthorDetails, AuthorResponse, AuthorWorksRequest, AuthorWorksResponse,
+    Author, AuthorDetails, AuthorResponse, AuthorWorksRequest, AuthorWorksResponse,
 };
 use crate::models::identifiers::OpenLibraryIdentifier;
-use crate::OpenLibraryError;
-use reqwest::Client;
+use crate::models::works::Work;
+use crate::models::{LinkName, WithRaw};
+use crate::pagination::{Page, PageCursor, Paginated};
+use crate::transport::HttpRequest;
+use crate::{Endpoint, OpenLibraryError};
+use futures::FutureExt;
 use serde::{Deserialize, Serialize};
-use std::convert::TryInto;
+use serde_json::Value;
+use std::convert::{TryFrom, TryInto};
 use url::Url;
 
+const DEFAULT_WORKS_PAGE_SIZE: u32 = 50;
+const DEFAULT_SEARCH_PAGE_SIZE: u32 = 100;
+
 #[derive(Clone)]
 pub struct AuthorClient {
-    client: Client,
+    client: HttpClient,
     host: Url,
 }
 
 impl AuthorClient {
-    pub fn new(client: &Client, host: &Url) -> Self {
+    pub fn new(client: &HttpClient, host: &Url) -> Self {
         Self {
             client: client.clone(),
             host: host.clone(),
@@ -27,51 +37,264 @@ impl AuthorClient {
         &self,
         identifier: OpenLibraryIdentifier,
     ) -> Result<AuthorDetails, OpenLibraryError> {
-        let url = self
-            .host
-            .join(format!("/authors/{}.json", identifier).as_str())?;
+        handle(
+            &self.client,
+            Endpoint::GetAuthor,
+            self.get_request(&identifier)?,
+        )
+        .await
+    }
 
-        handle(self.client.get(url)).await
+    pub async fn get_raw(
+        &self,
+        identifier: OpenLibraryIdentifier,
+    ) -> Result<Value, OpenLibraryError> {
+        handle(
+            &self.client,
+            Endpoint::GetAuthor,
+            self.get_request(&identifier)?,
+        )
+        .await
+    }
+
+    pub async fn get_with_raw(
+        &self,
+        identifier: OpenLibraryIdentifier,
+    ) -> Result<WithRaw<AuthorDetails>, OpenLibraryError> {
+        handle_with_raw(
+            &self.client,
+            Endpoint::GetAuthor,
+            self.get_request(&identifier)?,
+        )
+        .await
+    }
+
+    pub async fn get_many<I>(
+        &self,
+        identifiers: I,
+        options: BatchOptions,
+    ) -> BatchResponse<AuthorDetails>
+    where
+        I: IntoIterator<Item = OpenLibraryIdentifier>,
+    {
+        fetch_many(identifiers, options, |identifier| self.get(identifier)).await
     }
 
     pub async fn get_works<T>(&self, request: T) -> Result<AuthorWorksResponse, OpenLibraryError>
     where
         T: TryInto<AuthorWorksRequest>,
     {
-        let parameters: AuthorWorksRequest =
-            request
-                .try_into()
-                .map_err(|_e| OpenLibraryError::ParsingError {
-                    reason: format!("Unable to parse supplied object into a proper request object")
-                        .to_string(),
-                })?;
-        let limit = parameters.limit.unwrap_or(50);
-        let offset = parameters.offset.unwrap_or(0);
-        let url = self.host.join(
-            format!(
-                "/authors/{}/works.json?limit={}&offset={}",
-                parameters.identifier, limit, offset
-            )
-            .as_str(),
-        )?;
+        let parameters = Self::works_request(request)?;
+
+        handle(
+            &self.client,
+            Endpoint::GetAuthorWorks,
+            self.client.get(self.works_url(&parameters)?),
+        )
+        .await
+    }
+
+    pub async fn get_works_raw<T>(&self, request: T) -> Result<Value, OpenLibraryError>
+    where
+        T: TryInto<AuthorWorksRequest>,
+    {
+        let parameters = Self::works_request(request)?;
 
-        handle(self.client.get(url)).await
+        handle(
+            &self.client,
+            Endpoint::GetAuthorWorks,
+            self.client.get(self.works_url(&parameters)?),
+        )
+        .await
+    }
+
+    pub async fn get_works_with_raw<T>(
+        &self,
+        request: T,
+    ) -> Result<WithRaw<AuthorWorksResponse>, OpenLibraryError>
+    where
+        T: TryInto<AuthorWorksRequest>,
+    {
+        let parameters = Self::works_request(request)?;
+
+        handle_with_raw(
+            &self.client,
+            Endpoint::GetAuthorWorks,
+            self.client.get(self.works_url(&parameters)?),
+        )
+        .await
+    }
+
+    pub fn get_works_paginated<T>(&self, request: T) -> Result<Paginated<Work>, OpenLibraryError>
+    where
+        T: TryInto<AuthorWorksRequest>,
+    {
+        let parameters = Self::works_request(request)?;
+        let identifier = parameters.identifier;
+        let client = self.clone();
+
+        Ok(Paginated::new(
+            PageCursor::Offset(parameters.offset.unwrap_or(0)),
+            parameters.limit.unwrap_or(DEFAULT_WORKS_PAGE_SIZE),
+            move |cursor, page_size| {
+                let client = client.clone();
+                let identifier = identifier.clone();
+                async move {
+                    let parameters = match cursor {
+                        PageCursor::Link(url) => {
+                            let request = AuthorWorksRequest::try_from(url)?;
+                            AuthorWorksRequest {
+                                limit: request.limit.or(Some(page_size)),
+                                ..request
+                            }
+                        }
+                        PageCursor::Offset(offset) => AuthorWorksRequest {
+                            identifier,
+                            limit: Some(page_size),
+                            offset: Some(offset),
+                        },
+                    };
+
+                    let response: AuthorWorksResponse = handle(
+                        &client.client,
+                        Endpoint::GetAuthorWorks,
+                        client.client.get(client.works_url(&parameters)?),
+                    )
+                    .await?;
+                    let next = match response.links.get(&LinkName::Next) {
+                        Some(link) => Some(PageCursor::Link(client.host.join(link)?)),
+                        None => None,
+                    };
+
+                    Ok(Page {
+                        items: response.entries,
+                        next,
+                    })
+                }
+                .boxed()
+            },
+        ))
     }
 
     pub async fn search(&self, author_name: &str) -> Result<AuthorResponse, OpenLibraryError> {
-        let url = self.host.join("search/authors.json")?;
+        handle(
+            &self.client,
+            Endpoint::SearchAuthors,
+            self.search_request(author_name)?,
+        )
+        .await
+    }
 
+    pub async fn search_raw(&self, author_name: &str) -> Result<Value, OpenLibraryError> {
         handle(
-            self.client
-                .get(url)
-                .query(&[(QueryParameters::AuthorQuery, author_name)]),
+            &self.client,
+            Endpoint::SearchAuthors,
+            self.search_request(author_name)?,
+        )
+        .await
+    }
+
+    pub async fn search_with_raw(
+        &self,
+        author_name: &str,
+    ) -> Result<WithRaw<AuthorResponse>, OpenLibraryError> {
+        handle_with_raw(
+            &self.client,
+            Endpoint::SearchAuthors,
+            self.search_request(author_name)?,
         )
         .await
     }
+
+    pub fn search_paginated(&self, author_name: &str) -> Paginated<Author> {
+        let author_name = author_name.to_string();
+        let client = self.clone();
+
+        Paginated::new(
+            PageCursor::Offset(0),
+            DEFAULT_SEARCH_PAGE_SIZE,
+            move |cursor, page_size| {
+                let client = client.clone();
+                let author_name = author_name.clone();
+                async move {
+                    let request = match cursor {
+                        PageCursor::Link(url) => client.client.get(url),
+                        PageCursor::Offset(offset) => client
+                            .client
+                            .get(client.host.join("search/authors.json")?)
+                            .query(&[
+                                (QueryParameters::AuthorQuery, author_name),
+                                (QueryParameters::Offset, offset.to_string()),
+                                (QueryParameters::Limit, page_size.to_string()),
+                            ])?,
+                    };
+
+                    let response: AuthorResponse =
+                        handle(&client.client, Endpoint::SearchAuthors, request).await?;
+                    let offset = response.start.max(0) as u32 + response.docs.len() as u32;
+                    let next = match offset < response.num_found.max(0) as u32 {
+                        true => Some(PageCursor::Offset(offset)),
+                        false => None,
+                    };
+
+                    Ok(Page {
+                        items: response.docs,
+                        next,
+                    })
+                }
+                .boxed()
+            },
+        )
+    }
+
+    fn get_request(
+        &self,
+        identifier: &OpenLibraryIdentifier,
+    ) -> Result<HttpRequest, OpenLibraryError> {
+        let url = self
+            .host
+            .join(format!("/authors/{}.json", identifier).as_str())?;
+
+        Ok(self.client.get(url))
+    }
+
+    fn search_request(&self, author_name: &str) -> Result<HttpRequest, OpenLibraryError> {
+        self.client
+            .get(self.host.join("search/authors.json")?)
+            .query(&[(QueryParameters::AuthorQuery, author_name)])
+    }
+
+    fn works_request<T>(request: T) -> Result<AuthorWorksRequest, OpenLibraryError>
+    where
+        T: TryInto<AuthorWorksRequest>,
+    {
+        request
+            .try_into()
+            .map_err(|_e| OpenLibraryError::ParsingError {
+                reason: "Unable to parse supplied object into a proper request object".to_string(),
+            })
+    }
+
+    fn works_url(&self, parameters: &AuthorWorksRequest) -> Result<Url, OpenLibraryError> {
+        let limit = parameters.limit.unwrap_or(DEFAULT_WORKS_PAGE_SIZE);
+        let offset = parameters.offset.unwrap_or(0);
+
+        Ok(self.host.join(
+            format!(
+                "/authors/{}/works.json?limit={}&offset={}",
+                parameters.identifier, limit, offset
+            )
+            .as_str(),
+        )?)
+    }
 }
 
 #[derive(Deserialize, Serialize)]
 enum QueryParameters {
     #[serde(rename = "q")]
     AuthorQuery,
+    #[serde(rename = "limit")]
+    Limit,
+    #[serde(rename = "offset")]
+    Offset,
 }
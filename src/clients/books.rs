@@ -1,18 +1,21 @@
-use crate::clients::handle;
+use crate::batch::{fetch_many, BatchOptions, BatchResponse};
+use crate::clients::HttpClient;
+use crate::clients::{handle, handle_with_raw};
 use crate::models::books::{BibliographyKey, Book};
 use crate::models::identifiers::{
     Identifier, InternationalStandardBookNumber, OpenLibraryIdentifier,
 };
-use crate::models::OpenLibraryModel;
-use crate::OpenLibraryError;
-use reqwest::Client;
+use crate::models::{OpenLibraryModel, WithRaw};
+use crate::transport::HttpRequest;
+use crate::{Endpoint, OpenLibraryError};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::collections::HashMap;
 use url::Url;
 
 #[derive(Clone)]
 pub struct BooksClient {
-    client: Client,
+    client: HttpClient,
     host: Url,
 }
 
@@ -20,7 +23,7 @@ type BookSearchResponse = HashMap<BibliographyKey, Book>;
 impl OpenLibraryModel for BookSearchResponse {}
 
 impl BooksClient {
-    pub fn new(client: &Client, host: &Url) -> Self {
+    pub fn new(client: &HttpClient, host: &Url) -> Self {
         Self {
             client: client.clone(),
             host: host.clone(),
@@ -31,38 +34,151 @@ impl BooksClient {
         &self,
         isbn: InternationalStandardBookNumber,
     ) -> Result<Book, OpenLibraryError> {
+        handle(
+            &self.client,
+            Endpoint::GetBookByIsbn,
+            self.isbn_request(&isbn)?,
+        )
+        .await
+    }
+
+    pub async fn by_isbn_raw(
+        &self,
+        isbn: InternationalStandardBookNumber,
+    ) -> Result<Value, OpenLibraryError> {
+        handle(
+            &self.client,
+            Endpoint::GetBookByIsbn,
+            self.isbn_request(&isbn)?,
+        )
+        .await
+    }
+
+    pub async fn by_isbn_with_raw(
+        &self,
+        isbn: InternationalStandardBookNumber,
+    ) -> Result<WithRaw<Book>, OpenLibraryError> {
+        handle_with_raw(
+            &self.client,
+            Endpoint::GetBookByIsbn,
+            self.isbn_request(&isbn)?,
+        )
+        .await
+    }
+
+    pub async fn get(&self, identifier: OpenLibraryIdentifier) -> Result<Book, OpenLibraryError> {
+        handle(
+            &self.client,
+            Endpoint::GetBook,
+            self.get_request(&identifier)?,
+        )
+        .await
+    }
+
+    pub async fn get_raw(
+        &self,
+        identifier: OpenLibraryIdentifier,
+    ) -> Result<Value, OpenLibraryError> {
+        handle(
+            &self.client,
+            Endpoint::GetBook,
+            self.get_request(&identifier)?,
+        )
+        .await
+    }
+
+    pub async fn get_with_raw(
+        &self,
+        identifier: OpenLibraryIdentifier,
+    ) -> Result<WithRaw<Book>, OpenLibraryError> {
+        handle_with_raw(
+            &self.client,
+            Endpoint::GetBook,
+            self.get_request(&identifier)?,
+        )
+        .await
+    }
+
+    pub async fn get_many<I>(&self, identifiers: I, options: BatchOptions) -> BatchResponse<Book>
+    where
+        I: IntoIterator<Item = OpenLibraryIdentifier>,
+    {
+        fetch_many(identifiers, options, |identifier| self.get(identifier)).await
+    }
+
+    pub async fn search<'a, T: Into<&'a Vec<BibliographyKey>>>(
+        &self,
+        identifiers: T,
+    ) -> Result<BookSearchResponse, OpenLibraryError> {
+        handle(
+            &self.client,
+            Endpoint::SearchBooks,
+            self.search_request(identifiers.into())?,
+        )
+        .await
+    }
+
+    pub async fn search_raw<'a, T: Into<&'a Vec<BibliographyKey>>>(
+        &self,
+        identifiers: T,
+    ) -> Result<Value, OpenLibraryError> {
+        handle(
+            &self.client,
+            Endpoint::SearchBooks,
+            self.search_request(identifiers.into())?,
+        )
+        .await
+    }
+
+    pub async fn search_with_raw<'a, T: Into<&'a Vec<BibliographyKey>>>(
+        &self,
+        identifiers: T,
+    ) -> Result<WithRaw<BookSearchResponse>, OpenLibraryError> {
+        handle_with_raw(
+            &self.client,
+            Endpoint::SearchBooks,
+            self.search_request(identifiers.into())?,
+        )
+        .await
+    }
+
+    fn isbn_request(
+        &self,
+        isbn: &InternationalStandardBookNumber,
+    ) -> Result<HttpRequest, OpenLibraryError> {
         let url = self
             .host
             .join(format!("/isbn/{}.json", isbn.value()).as_str())?;
 
-        handle(self.client.get(url)).await
+        Ok(self.client.get(url))
     }
 
-    pub async fn get(&self, identifier: OpenLibraryIdentifier) -> Result<Book, OpenLibraryError> {
+    fn get_request(
+        &self,
+        identifier: &OpenLibraryIdentifier,
+    ) -> Result<HttpRequest, OpenLibraryError> {
         let url = self
             .host
             .join(format!("/books/{}.json", identifier.value()).as_str())?;
 
-        handle(self.client.get(url)).await
+        Ok(self.client.get(url))
     }
 
-    pub async fn search<'a, T: Into<&'a Vec<BibliographyKey>>>(
+    fn search_request(
         &self,
-        identifiers: T,
-    ) -> Result<BookSearchResponse, OpenLibraryError> {
+        identifiers: &[BibliographyKey],
+    ) -> Result<HttpRequest, OpenLibraryError> {
         let ids_filter = identifiers
-            .into()
             .iter()
             .map(|id| id.to_string())
             .collect::<Vec<String>>()
             .join(",");
 
-        handle(self.client.get(self.host.join("/api/books")?).query(&[
+        self.client.get(self.host.join("/api/books")?).query(&[
             (QueryParameters::BibliographyKeys, &ids_filter),
             (QueryParameters::Format, &String::from("json")),
             (QueryParameters::JavascriptCommand, &String::from("data")),
-        ]))
-        .await
+        ])
     }
 }
 
@@ -1,16 +1,26 @@
-use crate::models::account::{LoginRequest, ReadingLog, ReadingLogResponse, Session};
-use crate::{OpenLibraryAuthClient, OpenLibraryClient, OpenLibraryError, OpenLibraryErrorResponse};
-use http::Method;
+use crate::auth::{Authenticated, Credentials, LoginFailure};
+use crate::models::account::{
+    LoginRequest, NewList, ReadingLog, ReadingLogEntry, ReadingLogResponse, Session,
+};
+use crate::models::identifiers::OpenLibraryIdentifier;
+use crate::models::OpenLibraryResource;
+use crate::{OpenLibraryClient, OpenLibraryError, OpenLibraryErrorResponse};
+use futures::TryStreamExt;
+use http::{Method, StatusCode};
+use serde_json::json;
 use std::error::Error;
+use std::str::FromStr;
 use test_case::test_case;
 use url::Url;
-use wiremock::matchers::{body_json, header, method, path};
+use wiremock::matchers::{body_json, body_string, header, method, path, query_param};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
 #[tokio::test]
 async fn test_login_returns_success() -> Result<(), Box<dyn Error>> {
     let server = MockServer::start().await;
-    let client = OpenLibraryAuthClient::new(Some(Url::parse(server.uri().as_str())?))?;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .build()?;
 
     let expected = Session::from("mock_session_cookie".to_string(), "mock_user".to_string());
 
@@ -31,14 +41,17 @@ async fn test_login_returns_success() -> Result<(), Box<dyn Error>> {
         .login("mock_user".to_string(), "mock_password".to_string())
         .await?;
 
-    assert_eq!(expected, actual);
+    assert_eq!(actual.session(), Some(expected));
+    assert_eq!(client.session(), None);
     Ok(())
 }
 
 #[tokio::test]
 async fn test_login_returns_error_on_failure() -> Result<(), Box<dyn Error>> {
     let server = MockServer::start().await;
-    let client = OpenLibraryAuthClient::new(Some(Url::parse(server.uri().as_str())?))?;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .build()?;
 
     let expected = Session::from("mock_session_cookie".to_string(), "mock_user".to_string());
 
@@ -56,12 +69,14 @@ async fn test_login_returns_error_on_failure() -> Result<(), Box<dyn Error>> {
         .login("mock_user".to_string(), "mock_password".to_string())
         .await;
 
-    let error = actual.expect_err("Expected login to return an error!");
+    let error = actual
+        .map(|_client| ())
+        .expect_err("Expected login to return an error!");
     match error {
-        OpenLibraryError::ApiError {
-            status_code: _,
-            error: _,
-        } => Ok(()),
+        OpenLibraryError::ApiError { status_code, .. } => {
+            assert_eq!(status_code, StatusCode::BAD_REQUEST);
+            Ok(())
+        }
         _ => panic!(
             "Expected to receive an Api Error but received {:?} instead!",
             &error
@@ -69,6 +84,91 @@ async fn test_login_returns_error_on_failure() -> Result<(), Box<dyn Error>> {
     }
 }
 
+#[test_case(401, "account_user_notfound", LoginFailure::UnknownAccount; "unknown_account")]
+#[test_case(401, "account_incorrect_password", LoginFailure::IncorrectPassword; "incorrect_password")]
+#[test_case(403, "account_not_verified", LoginFailure::UnverifiedAccount; "unverified_account")]
+#[test_case(403, "account_blocked", LoginFailure::BlockedAccount; "blocked_account")]
+#[test_case(200, "account_locked", LoginFailure::LockedAccount; "locked_account")]
+#[test_case(400, "something_new", LoginFailure::Other("something_new".to_string()); "other")]
+#[tokio::test]
+async fn test_login_reports_failure_reason(
+    status: u16,
+    code: &str,
+    expected: LoginFailure,
+) -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .build()?;
+
+    Mock::given(method(Method::POST.as_str()))
+        .and(path("/account/login"))
+        .respond_with(ResponseTemplate::new(status).set_body_json(json!({ "error": code })))
+        .mount(&server)
+        .await;
+
+    match client
+        .login("mock_user".to_string(), "mock_password".to_string())
+        .await
+        .map(|_client| ())
+    {
+        Err(OpenLibraryError::LoginFailed { reason, error, .. }) => {
+            assert_eq!(reason, expected);
+            assert_eq!(error.map(|error| error.error).as_deref(), Some(code));
+            Ok(())
+        }
+        other => panic!(
+            "Expected a LoginFailed error but received {:?} instead!",
+            other
+        ),
+    }
+}
+
+#[tokio::test]
+async fn test_login_without_session_cookie_fails() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .build()?;
+
+    Mock::given(method(Method::POST.as_str()))
+        .and(path("/account/login"))
+        .respond_with(ResponseTemplate::new(503).set_body_json(json!({"error": "unavailable"})))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method(Method::POST.as_str()))
+        .and(path("/account/login"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("<html></html>"))
+        .mount(&server)
+        .await;
+
+    // Server errors aren't mistaken for a refused login
+    match client
+        .login("mock_user".to_string(), "mock_password".to_string())
+        .await
+        .map(|_client| ())
+    {
+        Err(OpenLibraryError::ServerError { .. }) => {}
+        other => panic!("Expected a ServerError but received {:?} instead!", other),
+    }
+
+    match client
+        .login("mock_user".to_string(), "mock_password".to_string())
+        .await
+        .map(|_client| ())
+    {
+        Err(OpenLibraryError::LoginFailed { reason, .. }) => {
+            assert_eq!(reason, LoginFailure::MissingSession);
+            Ok(())
+        }
+        other => panic!(
+            "Expected a LoginFailed error but received {:?} instead!",
+            other
+        ),
+    }
+}
+
 #[test_case(ReadingLog::AlreadyRead; "already_read")]
 #[test_case(ReadingLog::CurrentlyReading; "currently_reading")]
 #[test_case(ReadingLog::WantToRead; "want_to_read")]
@@ -149,13 +249,331 @@ async fn test_reading_log_endpoints_returns_failure_when_error_is_returned(
     let error = actual.err().unwrap();
 
     match error {
-        OpenLibraryError::ApiError {
-            status_code: _,
-            error: _,
-        } => Ok(()),
+        OpenLibraryError::ApiError { error, .. } => {
+            assert_eq!(
+                error.map(|error| error.error).as_deref(),
+                Some("Shelf want-to-read not found or not accessible")
+            );
+            Ok(())
+        }
         _ => panic!(
             "Expected call to return a Api Error but returned {:?} instead",
             error
         ),
     }
 }
+
+#[tokio::test]
+async fn test_reading_log_paginated_stops_when_predicate_matches() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+
+    let mock_session = Session::from("mock_session_cookie".to_string(), "mock_user".to_string());
+
+    let client = OpenLibraryClient::builder()
+        .with_session(&mock_session)
+        .with_host(Url::parse(server.uri().as_str())?)
+        .build()?;
+
+    let first_page: serde_json::Value =
+        serde_json::from_str(include_str!("resources/want-to-read.json"))?;
+    let mut second_page = first_page.clone();
+    second_page["reading_log_entries"][0]["work"]["title"] = json!("Stop Here");
+
+    Mock::given(method(Method::GET.as_str()))
+        .and(path("/people/mock_user/books/want-to-read.json"))
+        .and(query_param("page", "1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&first_page))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method(Method::GET.as_str()))
+        .and(path("/people/mock_user/books/want-to-read.json"))
+        .and(query_param("page", "2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&second_page))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let entries = client
+        .account
+        .get_reading_log_paginated(ReadingLog::WantToRead, "mock_user".to_string())?
+        .with_page_size(1)
+        .with_stop_predicate(|entry| entry.work.title == "Stop Here")
+        .try_collect::<Vec<ReadingLogEntry>>()
+        .await?;
+
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].work.title, "Atomic Habits");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_reading_log_paginated_follows_server_page_sizes() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .build()?;
+
+    let page: serde_json::Value =
+        serde_json::from_str(include_str!("resources/want-to-read.json"))?;
+    let entry = page["reading_log_entries"][0].clone();
+    let titled = |title: &str| {
+        let mut entry = entry.clone();
+        entry["work"]["title"] = json!(title);
+        entry
+    };
+
+    // The first page ignores the requested limit and the second comes back short
+    for (number, entries) in [
+        (
+            "1",
+            vec![titled("First"), titled("Second"), titled("Third")],
+        ),
+        ("2", vec![titled("Fourth")]),
+        ("3", vec![]),
+    ] {
+        Mock::given(method(Method::GET.as_str()))
+            .and(path("/people/mock_user/books/want-to-read.json"))
+            .and(query_param("page", number))
+            .and(query_param("limit", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "page": number.parse::<i16>()?,
+                "reading_log_entries": entries,
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+
+    let titles = client
+        .account
+        .get_reading_log_paginated(ReadingLog::WantToRead, "mock_user".to_string())?
+        .with_page_size(2)
+        .map_ok(|entry| entry.work.title)
+        .try_collect::<Vec<String>>()
+        .await?;
+
+    assert_eq!(titles, vec!["First", "Second", "Third", "Fourth"]);
+    Ok(())
+}
+
+async fn authenticated_client(
+    server: &MockServer,
+) -> Result<OpenLibraryClient<Authenticated>, Box<dyn Error>> {
+    let mock_session = Session::from("mock_session_cookie".to_string(), "mock_user".to_string());
+
+    Ok(OpenLibraryClient::builder()
+        .with_session(&mock_session)
+        .with_host(Url::parse(server.uri().as_str())?)
+        .build()?
+        .authenticated()?)
+}
+
+#[test_case(ReadingLog::AlreadyRead, "3"; "already_read")]
+#[test_case(ReadingLog::CurrentlyReading, "2"; "currently_reading")]
+#[test_case(ReadingLog::WantToRead, "1"; "want_to_read")]
+#[tokio::test]
+async fn test_reading_log_edits_post_to_bookshelf(
+    reading_log: ReadingLog,
+    bookshelf_id: &str,
+) -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = authenticated_client(&server).await?;
+
+    for action in ["add", "remove"] {
+        Mock::given(method(Method::POST.as_str()))
+            .and(path("/works/OL45804W/bookshelves.json"))
+            .and(header(http::header::COOKIE.as_str(), "mock_session_cookie"))
+            .and(body_string(format!(
+                "action={}&bookshelf_id={}&redir=false",
+                action, bookshelf_id
+            )))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({"bookshelves_affected": []})),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+
+    let work = OpenLibraryIdentifier::from_str("OL45804W")?;
+    client
+        .account
+        .add_to_reading_log(&work, reading_log.clone())
+        .await?;
+    client
+        .account
+        .remove_from_reading_log(&work, reading_log)
+        .await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rating_a_work() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = authenticated_client(&server).await?;
+    let work = OpenLibraryIdentifier::from_str("OL45804W")?;
+
+    Mock::given(method(Method::POST.as_str()))
+        .and(path("/works/OL45804W/ratings.json"))
+        .and(body_string("rating=4&redir=false"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"success": "rating added"})))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method(Method::POST.as_str()))
+        .and(path("/works/OL45804W/ratings.json"))
+        .and(body_string("action=delete&redir=false"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(json!({"success": "removed rating"})),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.account.rate(&work, 4).await?;
+    client.account.remove_rating(&work).await?;
+
+    match client.account.rate(&work, 6).await {
+        Err(OpenLibraryError::ParsingError { .. }) => Ok(()),
+        other => panic!(
+            "Expected a ParsingError for an out of range rating but received {:?} instead!",
+            other
+        ),
+    }
+}
+
+#[tokio::test]
+async fn test_managing_lists() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = authenticated_client(&server).await?;
+
+    Mock::given(method(Method::POST.as_str()))
+        .and(path("/people/mock_user/lists.json"))
+        .and(body_json(json!({
+            "name": "Summer",
+            "description": "Beach reads",
+            "seeds": [{"key": "/works/OL45804W"}],
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "key": "/people/mock_user/lists/OL1L",
+            "revision": 1,
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method(Method::POST.as_str()))
+        .and(path("/people/mock_user/lists/OL1L/seeds.json"))
+        .and(body_json(json!({
+            "add": [{"key": "/books/OL7353617M"}],
+            "remove": [],
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"revision": 2})))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method(Method::POST.as_str()))
+        .and(path("/people/mock_user/lists/OL1L/delete.json"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"status": "ok"})))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let list = client
+        .account
+        .create_list(
+            &NewList::new("Summer")
+                .with_description("Beach reads")
+                .with_seed(OpenLibraryResource::Work("OL45804W".to_string())),
+        )
+        .await?;
+    assert_eq!(list, OpenLibraryIdentifier::from_str("OL1L")?);
+
+    client
+        .account
+        .add_to_list(
+            &list,
+            &[OpenLibraryResource::Book("OL7353617M".to_string())],
+        )
+        .await?;
+    client.account.delete_list(&list).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_write_reports_error_document() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = authenticated_client(&server).await?;
+
+    Mock::given(method(Method::POST.as_str()))
+        .and(path("/works/OL45804W/ratings.json"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(OpenLibraryErrorResponse {
+                error: "Invalid work".to_string(),
+            }),
+        )
+        .mount(&server)
+        .await;
+
+    match client
+        .account
+        .rate(&OpenLibraryIdentifier::from_str("OL45804W")?, 5)
+        .await
+    {
+        Err(OpenLibraryError::ApiError { error, .. }) => {
+            assert_eq!(
+                error.map(|error| error.error).as_deref(),
+                Some("Invalid work")
+            );
+            Ok(())
+        }
+        other => panic!("Expected an ApiError but received {:?} instead!", other),
+    }
+}
+
+#[tokio::test]
+async fn test_authenticating_requires_a_session_or_credentials() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let anonymous = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .build()?;
+
+    match anonymous.authenticated().map(|_client| ()) {
+        Err(OpenLibraryError::NotAuthenticated { .. }) => {}
+        other => panic!(
+            "Expected a NotAuthenticated error but received {:?} instead!",
+            other
+        ),
+    }
+
+    // With credentials but no session yet, the first write logs in before sending the change
+    Mock::given(method(Method::POST.as_str()))
+        .and(path("/account/login"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .append_header(http::header::SET_COOKIE.as_str(), "session=fresh; Path=/"),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method(Method::POST.as_str()))
+        .and(path("/people/mock_user/lists/OL1L/delete.json"))
+        .and(header(http::header::COOKIE.as_str(), "session=fresh"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"status": "ok"})))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .with_credentials_provider(Credentials::new("mock_user", "mock_password"))
+        .build()?
+        .authenticated()?;
+    client
+        .account
+        .delete_list(&OpenLibraryIdentifier::from_str("OL1L")?)
+        .await?;
+    Ok(())
+}
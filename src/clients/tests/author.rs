@@ -1,8 +1,13 @@
-use crate::models::authors::AuthorResponse;
+use crate::models::authors::{Author, AuthorResponse};
+use crate::models::identifiers::OpenLibraryIdentifier;
+use crate::models::works::Work;
 use crate::{OpenLibraryClient, OpenLibraryError};
-use http::Method;
+use futures::TryStreamExt;
+use http::{Method, StatusCode};
 use reqwest::Url;
+use serde_json::json;
 use std::error::Error;
+use std::str::FromStr;
 use wiremock::matchers::{method, path, query_param};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
@@ -53,13 +58,95 @@ async fn test_author_search_returns_failure_when_request_fails() -> Result<(), B
     let error = actual.expect_err("Expected Author Search call to return an error but it didn't!");
 
     match &error {
-        OpenLibraryError::ApiError {
-            status_code: _,
-            error: _,
-        } => Ok(()),
+        OpenLibraryError::ServerError { status_code, .. } => {
+            assert_eq!(*status_code, StatusCode::INTERNAL_SERVER_ERROR);
+            Ok(())
+        }
         _ => panic!(
-            "Expected to received an API error, but received {:?} instead!",
+            "Expected to received a server error, but received {:?} instead!",
             error
         ),
     }
 }
+
+#[tokio::test]
+async fn test_author_get_works_paginated_follows_next_links() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .build()?;
+
+    let work = |id: &str| {
+        json!({
+            "title": format!("Work {}", id),
+            "key": format!("/works/{}", id),
+            "authors": [],
+            "latest_revision": 1,
+            "revision": 1
+        })
+    };
+
+    Mock::given(method(Method::GET.as_str()))
+        .and(path("/authors/OL23919A/works.json"))
+        .and(query_param("offset", "0"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "links": { "next": "/authors/OL23919A/works.json?offset=2" },
+            "size": 3,
+            "entries": [work("OL1W"), work("OL2W")]
+        })))
+        .mount(&server)
+        .await;
+
+    Mock::given(method(Method::GET.as_str()))
+        .and(path("/authors/OL23919A/works.json"))
+        .and(query_param("offset", "2"))
+        .and(query_param("limit", "2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "size": 3,
+            "entries": [work("OL3W")]
+        })))
+        .mount(&server)
+        .await;
+
+    let works = client
+        .author
+        .get_works_paginated(OpenLibraryIdentifier::from_str("OL23919A")?)?
+        .with_page_size(2)
+        .try_collect::<Vec<Work>>()
+        .await?;
+
+    let titles = works.into_iter().map(|x| x.title).collect::<Vec<String>>();
+    assert_eq!(titles, vec!["Work OL1W", "Work OL2W", "Work OL3W"]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_author_search_paginated_stops_at_max_items() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .build()?;
+
+    let mut page: serde_json::Value =
+        serde_json::from_str(include_str!("resources/search_author.json"))?;
+    page["numFound"] = json!(10);
+
+    Mock::given(method(Method::GET.as_str()))
+        .and(path("/search/authors.json"))
+        .and(query_param("q", "j.k. rowling"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&page))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let authors = client
+        .author
+        .search_paginated("j.k. rowling")
+        .with_page_size(1)
+        .with_max_items(2)
+        .try_collect::<Vec<Author>>()
+        .await?;
+
+    assert_eq!(authors.len(), 2);
+    Ok(())
+}
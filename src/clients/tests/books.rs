@@ -1,6 +1,6 @@
 use crate::models::books::{BibliographyKey, Book};
 use crate::{OpenLibraryClient, OpenLibraryError};
-use http::Method;
+use http::{Method, StatusCode};
 use reqwest::Url;
 use std::collections::HashMap;
 use std::error::Error;
@@ -68,12 +68,12 @@ async fn test_book_search_returns_failure_when_request_fails() -> Result<(), Box
     let actual = client.books.search(&identifiers).await;
     let error = actual.expect_err("Expected Book Search call to return an error but it didn't!");
     match &error {
-        OpenLibraryError::ApiError {
-            status_code: _,
-            error: _,
-        } => Ok(()),
+        OpenLibraryError::ServerError { status_code, .. } => {
+            assert_eq!(*status_code, StatusCode::INTERNAL_SERVER_ERROR);
+            Ok(())
+        }
         _ => panic!(
-            "Expected to received an API error, but received {:?} instead!",
+            "Expected to received a server error, but received {:?} instead!",
             error
         ),
     }
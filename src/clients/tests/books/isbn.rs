@@ -45,12 +45,15 @@ async fn test_get_isbn_returns_error_when_invalid_json_returned() -> Result<(),
         .mount(&server)
         .await;
 
-    let actual = client.books.by_isbn(isbn).await;
+    let actual = client.books.by_isbn(isbn.clone()).await;
     let error =
         actual.expect_err("Expected by_isbn call to return an error but returned successfully!");
 
     match &error {
-        OpenLibraryError::JsonParseError { source: _ } => Ok(()),
+        OpenLibraryError::DeserializationError { url, .. } => {
+            assert_eq!(url.path(), format!("/isbn/{}.json", isbn.value()));
+            Ok(())
+        }
         _ => panic!(
             "Expected to received an API Not Found error, but received {:?} instead!",
             error
@@ -73,15 +76,15 @@ async fn test_get_isbn_returns_error_when_book_does_not_exist() -> Result<(), Bo
         .mount(&server)
         .await;
 
-    let actual = client.books.by_isbn(isbn).await;
+    let actual = client.books.by_isbn(isbn.clone()).await;
     let error =
         actual.expect_err("Expected by_isbn call to return an error but returned successfully!");
 
     match &error {
-        OpenLibraryError::ApiError {
-            status_code: http::status::StatusCode::NOT_FOUND,
-            error: _,
-        } => Ok(()),
+        OpenLibraryError::NotFound { resource, .. } => {
+            assert_eq!(resource.path(), format!("/isbn/{}.json", isbn.value()));
+            Ok(())
+        }
         _ => panic!(
             "Expected to received an API Not Found error, but received {:?} instead!",
             error
@@ -1,28 +1,79 @@
-use crate::clients::handle;
+use crate::batch::{fetch_many, BatchOptions, BatchResponse};
+use crate::clients::HttpClient;
+use crate::clients::{handle, handle_with_raw};
 use crate::models::identifiers::{Identifier, OpenLibraryIdentifier};
 use crate::models::works::Work;
-use crate::OpenLibraryError;
-use reqwest::{Client, Url};
+use crate::models::WithRaw;
+use crate::transport::HttpRequest;
+use crate::{Endpoint, OpenLibraryError};
+use reqwest::Url;
+use serde_json::Value;
 
 #[derive(Clone)]
 pub struct WorksClient {
-    client: Client,
+    client: HttpClient,
     host: Url,
 }
 
 impl WorksClient {
-    pub fn new(client: &Client, host: &Url) -> Self {
+    pub fn new(client: &HttpClient, host: &Url) -> Self {
         Self {
             client: client.clone(),
             host: host.clone(),
         }
     }
 
+    pub async fn get_many<I>(&self, identifiers: I, options: BatchOptions) -> BatchResponse<Work>
+    where
+        I: IntoIterator<Item = OpenLibraryIdentifier>,
+    {
+        fetch_many(identifiers, options, |identifier| async move {
+            self.get(&identifier).await
+        })
+        .await
+    }
+
     pub async fn get(&self, identifier: &OpenLibraryIdentifier) -> Result<Work, OpenLibraryError> {
+        handle(
+            &self.client,
+            Endpoint::GetWork,
+            self.get_request(identifier)?,
+        )
+        .await
+    }
+
+    pub async fn get_raw(
+        &self,
+        identifier: &OpenLibraryIdentifier,
+    ) -> Result<Value, OpenLibraryError> {
+        handle(
+            &self.client,
+            Endpoint::GetWork,
+            self.get_request(identifier)?,
+        )
+        .await
+    }
+
+    pub async fn get_with_raw(
+        &self,
+        identifier: &OpenLibraryIdentifier,
+    ) -> Result<WithRaw<Work>, OpenLibraryError> {
+        handle_with_raw(
+            &self.client,
+            Endpoint::GetWork,
+            self.get_request(identifier)?,
+        )
+        .await
+    }
+
+    fn get_request(
+        &self,
+        identifier: &OpenLibraryIdentifier,
+    ) -> Result<HttpRequest, OpenLibraryError> {
         let url = self
             .host
             .join(format!("/works/{}.json", identifier.value()).as_str())?;
 
-        handle(self.client.get(url)).await
+        Ok(self.client.get(url))
     }
 }
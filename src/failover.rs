@@ -0,0 +1,326 @@
+use crate::transport::{HttpRequest, HttpResponse, HttpTransport};
+use crate::OpenLibraryError;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use http::header::COOKIE;
+use http::{Method, StatusCode};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use url::Url;
+
+/// Controls when a host is taken out of rotation. A host is ejected after `failure_threshold`
+/// consecutive failures, i.e. server errors or transport failures, and receives a single trial
+/// request once `cooldown` has passed, which either restores it or ejects it again. Other requests
+/// skip the host while the trial is in flight.
+#[derive(Clone, Debug)]
+pub struct FailoverPolicy {
+    failure_threshold: u32,
+    cooldown: Duration,
+}
+
+impl Default for FailoverPolicy {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 3,
+            cooldown: Duration::from_secs(30),
+        }
+    }
+}
+
+impl FailoverPolicy {
+    pub fn with_failure_threshold(self, failure_threshold: u32) -> Self {
+        Self {
+            failure_threshold: failure_threshold.max(1),
+            ..self
+        }
+    }
+
+    pub fn with_cooldown(self, cooldown: Duration) -> Self {
+        Self { cooldown, ..self }
+    }
+}
+
+/// A snapshot of how a host has been responding.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HostHealth {
+    pub host: Url,
+    pub healthy: bool,
+    pub consecutive_failures: u32,
+}
+
+#[derive(Default)]
+struct Circuit {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+    // Set while the single trial request of a host whose cooldown has passed is in flight
+    trial: bool,
+}
+
+impl Circuit {
+    fn admits(&self, now: Instant) -> bool {
+        match self.open_until {
+            Some(open_until) => open_until <= now && !self.trial,
+            None => true,
+        }
+    }
+}
+
+/// The hosts reads may be served from, in order of preference, along with the primary host which
+/// URLs are built against and which receives every write.
+#[derive(Clone)]
+pub(crate) struct HostPool {
+    primary: Url,
+    hosts: Vec<Url>,
+    circuits: Arc<Mutex<HashMap<Url, Circuit>>>,
+    policy: FailoverPolicy,
+}
+
+impl HostPool {
+    pub(crate) fn new(primary: Url, hosts: Vec<Url>, policy: FailoverPolicy) -> Self {
+        Self {
+            primary,
+            hosts,
+            circuits: Arc::new(Mutex::new(HashMap::new())),
+            policy,
+        }
+    }
+
+    pub(crate) fn health(&self) -> Vec<HostHealth> {
+        let circuits = self
+            .circuits
+            .lock()
+            .unwrap_or_else(|error| error.into_inner());
+        let now = Instant::now();
+
+        std::iter::once(&self.primary)
+            .chain(self.hosts.iter().filter(|host| **host != self.primary))
+            .map(|host| {
+                let circuit = circuits.get(host);
+                HostHealth {
+                    host: host.clone(),
+                    healthy: circuit
+                        .and_then(|circuit| circuit.open_until)
+                        .is_none_or(|open_until| open_until <= now),
+                    consecutive_failures: circuit
+                        .map(|circuit| circuit.consecutive_failures)
+                        .unwrap_or_default(),
+                }
+            })
+            .collect()
+    }
+
+    /// The hosts to try for the request in order, and whether they're tried regardless of their
+    /// circuits. Writes and account requests stay on the primary, since sessions are only valid
+    /// there, as do URLs outside of the primary host.
+    fn candidates(&self, request: &HttpRequest) -> (Vec<Url>, bool) {
+        let relative = match request.url.as_str().strip_prefix(self.primary.as_str()) {
+            Some(relative) => relative,
+            None => return (vec![], true),
+        };
+        let pinned = !matches!(request.method, Method::GET | Method::HEAD)
+            || relative.starts_with("account")
+            || relative.starts_with("people/");
+        if pinned || self.hosts.is_empty() {
+            return (vec![self.primary.clone()], true);
+        }
+
+        let circuits = self
+            .circuits
+            .lock()
+            .unwrap_or_else(|error| error.into_inner());
+        let now = Instant::now();
+        let available = self
+            .hosts
+            .iter()
+            .filter(|host| {
+                circuits
+                    .get(*host)
+                    .is_none_or(|circuit| circuit.admits(now))
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        // With every host ejected, trying them all beats failing without sending anything
+        match available.is_empty() {
+            true => (self.hosts.clone(), true),
+            false => (available, false),
+        }
+    }
+
+    /// Whether the request may be sent to the host, claiming the host's trial request once its
+    /// cooldown has passed.
+    fn admit(&self, host: &Url) -> Admission {
+        let mut circuits = self
+            .circuits
+            .lock()
+            .unwrap_or_else(|error| error.into_inner());
+        let circuit = match circuits.get_mut(host) {
+            Some(circuit) if circuit.open_until.is_some() => circuit,
+            _ => return Admission::Admitted,
+        };
+
+        match circuit.admits(Instant::now()) {
+            true => {
+                circuit.trial = true;
+                Admission::Trial
+            }
+            false => Admission::Refused,
+        }
+    }
+
+    /// Gives up the host's trial without judging the host's health.
+    fn release(&self, host: &Url) {
+        if let Some(circuit) = self
+            .circuits
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+            .get_mut(host)
+        {
+            circuit.trial = false;
+        }
+    }
+
+    fn record_success(&self, host: &Url) {
+        self.circuits
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+            .remove(host);
+    }
+
+    fn record_failure(&self, host: &Url) {
+        let mut circuits = self
+            .circuits
+            .lock()
+            .unwrap_or_else(|error| error.into_inner());
+        let circuit = circuits.entry(host.clone()).or_default();
+        circuit.consecutive_failures += 1;
+
+        if circuit.consecutive_failures >= self.policy.failure_threshold {
+            tracing::warn!(
+                host = %host,
+                failures = circuit.consecutive_failures,
+                "Ejecting an Open Library host after consecutive failures"
+            );
+            circuit.open_until = Some(Instant::now() + self.policy.cooldown);
+        }
+    }
+
+    fn rebase(&self, request: &HttpRequest, host: &Url) -> Result<HttpRequest, OpenLibraryError> {
+        let mut request = request.clone();
+        if *host == self.primary {
+            return Ok(request);
+        }
+
+        let relative = request
+            .url
+            .as_str()
+            .strip_prefix(self.primary.as_str())
+            .unwrap_or_default()
+            .to_string();
+        request.url = host.join(relative.as_str())?;
+        // The session belongs to the primary host, mirrors have no business seeing it
+        request.headers.remove(COOKIE);
+        Ok(request)
+    }
+}
+
+enum Admission {
+    Admitted,
+    Trial,
+    Refused,
+}
+
+/// Holds a host's trial request, giving it up once the request is done with.
+struct Trial<'a> {
+    pool: &'a HostPool,
+    host: &'a Url,
+}
+
+impl Drop for Trial<'_> {
+    fn drop(&mut self) {
+        self.pool.release(self.host);
+    }
+}
+
+/// Sends reads to the first healthy host, moving on to the next host when one fails or doesn't
+/// have the requested resource.
+pub(crate) struct FailoverTransport {
+    pool: HostPool,
+    inner: Arc<dyn HttpTransport>,
+}
+
+impl FailoverTransport {
+    pub(crate) fn new(pool: HostPool, inner: Arc<dyn HttpTransport>) -> Self {
+        Self { pool, inner }
+    }
+}
+
+impl HttpTransport for FailoverTransport {
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, OpenLibraryError>> {
+        async move {
+            let (candidates, forced) = self.pool.candidates(&request);
+            if candidates.is_empty() {
+                return self.inner.send(request).await;
+            }
+
+            let mut result = None;
+            for (index, host) in candidates.iter().enumerate() {
+                let rebased = self.pool.rebase(&request, host)?;
+                let admission = match forced {
+                    true => Admission::Admitted,
+                    false => self.pool.admit(host),
+                };
+                // Released even when the request is cancelled, e.g. by a timeout, so the host
+                // isn't left waiting on a trial which never finishes
+                let _trial = match admission {
+                    Admission::Admitted => None,
+                    Admission::Trial => Some(Trial {
+                        pool: &self.pool,
+                        host,
+                    }),
+                    // Another request claimed the host's trial since the candidates were picked
+                    Admission::Refused => continue,
+                };
+                let attempt = self.inner.send(rebased).await;
+                let fall_back = match &attempt {
+                    Ok(response) if response.status.is_server_error() => {
+                        self.pool.record_failure(host);
+                        true
+                    }
+                    Ok(response) => {
+                        self.pool.record_success(host);
+                        // A mirror may be missing the resource, or refuse it, where others won't
+                        matches!(
+                            response.status,
+                            StatusCode::UNAUTHORIZED
+                                | StatusCode::FORBIDDEN
+                                | StatusCode::NOT_FOUND
+                                | StatusCode::TOO_MANY_REQUESTS
+                        )
+                    }
+                    Err(error) if error.is_transient() => {
+                        self.pool.record_failure(host);
+                        true
+                    }
+                    Err(_) => false,
+                };
+
+                result = Some(attempt);
+                if !fall_back {
+                    break;
+                }
+                if let Some(next) = candidates.get(index + 1) {
+                    tracing::debug!(host = %host, next = %next, "Falling back to the next Open Library host");
+                }
+            }
+
+            match result {
+                Some(result) => result,
+                // Every host was waiting on a trial, which the primary is best placed to ride out
+                None => self.inner.send(request).await,
+            }
+        }
+        .boxed()
+    }
+}
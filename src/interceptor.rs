@@ -0,0 +1,25 @@
+use crate::transport::{HttpRequest, HttpResponse};
+use crate::OpenLibraryError;
+
+/// Hooks into every request sent by the client's sub-clients. Interceptors run in the order they
+/// were added to the builder for requests and in reverse order for responses.
+pub trait Interceptor: Send + Sync {
+    /// Inspects or modifies an outgoing request. Returning a response short-circuits the chain,
+    /// skipping any later interceptors and the transport.
+    fn on_request(
+        &self,
+        _request: &mut HttpRequest,
+    ) -> Result<Option<HttpResponse>, OpenLibraryError> {
+        Ok(None)
+    }
+
+    /// Inspects or modifies a response before it is handed back to the caller. Runs for every
+    /// attempt when requests are retried.
+    fn on_response(
+        &self,
+        _request: &HttpRequest,
+        _response: &mut HttpResponse,
+    ) -> Result<(), OpenLibraryError> {
+        Ok(())
+    }
+}
@@ -1,44 +1,240 @@
+use crate::auth::{Anonymous, Authenticated, Authenticator, CredentialsProvider, LoginFailure};
+use crate::cache::{CacheConfig, CacheMode, CacheStats, ResponseCache};
+use crate::cassette::{Cassette, CassetteTransport};
 use crate::clients::account::AccountClient;
 use crate::clients::author::AuthorClient;
 use crate::clients::works::WorksClient;
+use crate::clients::{Coalescer, HttpClient};
+use crate::failover::{FailoverPolicy, FailoverTransport, HostHealth, HostPool};
+use crate::interceptor::Interceptor;
 use crate::models::account::Session;
+use crate::models::DeserializationMode;
+use crate::rate_limit::{RateLimit, RateLimitMode, RateLimiter};
+use crate::retry::{RetryAttempt, RetryPolicy};
+use crate::transport::{HttpResponse, HttpTransport, ReqwestTransport};
 use clients::books::BooksClient;
-use reqwest::header::{HeaderMap, HeaderValue};
-use reqwest::{ClientBuilder, Error, StatusCode};
+use reqwest::header::{HeaderMap, HeaderValue, LOCATION, USER_AGENT};
+use reqwest::{Certificate, ClientBuilder, Error, Proxy, StatusCode};
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::Duration;
 use thiserror::Error;
 use url::{ParseError, Url};
 
+pub mod auth;
+pub mod batch;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod cache;
+pub mod cassette;
 mod clients;
+pub mod failover;
 mod format;
+pub mod interceptor;
 pub mod models;
+pub mod pagination;
+pub mod rate_limit;
+pub mod retry;
+#[cfg(feature = "tower")]
+pub mod service;
+mod telemetry;
+#[cfg(feature = "test-support")]
+pub mod test_support;
 #[cfg(test)]
 mod tests;
+pub mod transport;
 
 #[derive(Debug, Error)]
 pub enum OpenLibraryError {
     #[error(
-        "Received an {:?} response from the Open Library API: {:?}",
+        "Received an {:?} response from the Open Library API for {}: {:?}",
         status_code,
+        url,
         error
     )]
     ApiError {
+        url: Url,
         status_code: StatusCode,
         error: Option<OpenLibraryErrorResponse>,
     },
+    #[error("An error occurred while using the cassette: {}", reason)]
+    CassetteError { reason: String },
+    #[error("A request shared with other callers failed: {}", source)]
+    CoalescedRequestFailed { source: Arc<OpenLibraryError> },
     #[error("Unable to build HTTP client: {}", source)]
     ClientBuildingError { source: reqwest::Error },
+    #[error(
+        "Unable to deserialize the response from {} at `{}`: {}",
+        url,
+        source.path(),
+        source.inner()
+    )]
+    DeserializationError {
+        url: Url,
+        source: Box<serde_path_to_error::Error<serde_json::Error>>,
+        /// The start of the response body, when captured with
+        /// [`OpenLibraryClientBuilder::with_error_body_capture`].
+        body: Option<String>,
+    },
     #[error("An internal error occurred: {}", reason)]
     InternalError { reason: String },
     #[error("An error occurred while parsing json: {}", source)]
-    JsonParseError { source: reqwest::Error },
+    JsonParseError { source: serde_json::Error },
+    #[error("Unable to log in ({}): {}", status_code, reason)]
+    LoginFailed {
+        reason: LoginFailure,
+        status_code: StatusCode,
+        error: Option<OpenLibraryErrorResponse>,
+    },
     #[error("The operation ({}) requires authentication to be provided!", reason)]
     NotAuthenticated { reason: String },
+    #[error("The requested resource ({}) does not exist: {:?}", resource, error)]
+    NotFound {
+        resource: Url,
+        error: Option<OpenLibraryErrorResponse>,
+    },
     #[error("An error occurred while trying to parse a value: {}", reason)]
     ParsingError { reason: String },
+    #[error(
+        "The client side rate limit for {} was exceeded, retry in {:?}",
+        host,
+        retry_after
+    )]
+    RateLimitExceeded { host: String, retry_after: Duration },
+    #[error(
+        "The Open Library API rate limited the request to {}, retry in {:?}",
+        url,
+        retry_after
+    )]
+    RateLimited {
+        url: Url,
+        retry_after: Option<Duration>,
+    },
+    #[error(
+        "The request to {} was redirected ({}) to {:?} which was not followed",
+        url,
+        status_code,
+        location
+    )]
+    Redirected {
+        url: Url,
+        status_code: StatusCode,
+        location: Option<String>,
+    },
     #[error("An error occurred while sending HTTP request: {}", source)]
     RequestFailed { source: reqwest::Error },
+    #[error("The request failed after {} attempts: {}", attempts.len(), source)]
+    RetriesExhausted {
+        attempts: Vec<RetryAttempt>,
+        source: Box<OpenLibraryError>,
+    },
+    #[error(
+        "The Open Library API failed to handle the request to {} ({}): {:?}",
+        url,
+        status_code,
+        error
+    )]
+    ServerError {
+        url: Url,
+        status_code: StatusCode,
+        error: Option<OpenLibraryErrorResponse>,
+    },
+    #[error("Unable to access the stored session: {}", reason)]
+    SessionStoreError { reason: String },
+    #[error("The request to {} timed out", url)]
+    Timeout { url: Url },
+    #[error("The HTTP transport failed to complete the request: {}", source)]
+    TransportError {
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    #[error(
+        "The request to {} was not authorized ({}): {:?}",
+        url,
+        status_code,
+        error
+    )]
+    Unauthorized {
+        url: Url,
+        status_code: StatusCode,
+        error: Option<OpenLibraryErrorResponse>,
+    },
+}
+
+impl OpenLibraryError {
+    /// Maps an unsuccessful response to the most specific error, keeping any error message the
+    /// API included in the body.
+    pub(crate) fn from_response(response: &HttpResponse) -> Self {
+        let url = response.url.clone();
+        let status_code = response.status;
+        let error = serde_json::from_slice::<OpenLibraryErrorResponse>(&response.body).ok();
+
+        match status_code {
+            StatusCode::NOT_FOUND => OpenLibraryError::NotFound {
+                resource: url,
+                error,
+            },
+            StatusCode::TOO_MANY_REQUESTS => OpenLibraryError::RateLimited {
+                retry_after: retry::retry_after(&response.headers),
+                url,
+            },
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => OpenLibraryError::Unauthorized {
+                url,
+                status_code,
+                error,
+            },
+            status_code if status_code.is_redirection() => OpenLibraryError::Redirected {
+                location: response
+                    .headers
+                    .get(LOCATION)
+                    .and_then(|value| value.to_str().ok())
+                    .map(str::to_string),
+                url,
+                status_code,
+            },
+            status_code if status_code.is_server_error() => OpenLibraryError::ServerError {
+                url,
+                status_code,
+                error,
+            },
+            status_code => OpenLibraryError::ApiError {
+                url,
+                status_code,
+                error,
+            },
+        }
+    }
+
+    /// Whether the request failed to complete because of the network, e.g. a timeout or a dropped
+    /// connection, rather than because of the API's answer.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            OpenLibraryError::CoalescedRequestFailed { source } => source.is_transient(),
+            OpenLibraryError::RequestFailed { source } => {
+                source.is_timeout() || source.is_connect()
+            }
+            OpenLibraryError::Timeout { .. } => true,
+            OpenLibraryError::TransportError { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// Whether sending the same request again later may succeed.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            OpenLibraryError::CoalescedRequestFailed { source } => source.is_retryable(),
+            OpenLibraryError::RateLimitExceeded { .. } => true,
+            OpenLibraryError::RateLimited { .. } => true,
+            OpenLibraryError::ServerError { status_code, .. } => matches!(
+                *status_code,
+                StatusCode::INTERNAL_SERVER_ERROR
+                    | StatusCode::BAD_GATEWAY
+                    | StatusCode::SERVICE_UNAVAILABLE
+                    | StatusCode::GATEWAY_TIMEOUT
+            ),
+            error => error.is_transient(),
+        }
+    }
 }
 
 impl From<reqwest::Error> for OpenLibraryError {
@@ -55,112 +251,641 @@ impl From<ParseError> for OpenLibraryError {
     }
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+/// The Open Library API operations performed by this client.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Endpoint {
+    GetAuthor,
+    GetAuthorWorks,
+    SearchAuthors,
+    GetBook,
+    GetBookByIsbn,
+    SearchBooks,
+    GetWork,
+    GetReadingLog,
+    Login,
+    Logout,
+    WhoAmI,
+    UpdateReadingLog,
+    RateWork,
+    CreateList,
+    UpdateList,
+    DeleteList,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct OpenLibraryErrorResponse {
     pub error: String,
 }
 
-pub struct OpenLibraryAuthClient {
-    account: AccountClient,
+/// A client for the Open Library API. Clients start out [`Anonymous`], [`login`] returns an
+/// [`Authenticated`] client which can also make changes to the account.
+///
+/// [`login`]: OpenLibraryClient::login
+pub struct OpenLibraryClient<A = Anonymous> {
+    pub account: AccountClient<A>,
+    pub author: AuthorClient,
+    pub books: BooksClient,
+    pub works: WorksClient,
+    client: HttpClient,
+    host: Url,
 }
 
-impl OpenLibraryAuthClient {
-    //TODO: this is odd to consume with having to know to pass in None to get production url
-    pub fn new(host: Option<Url>) -> Result<OpenLibraryAuthClient, OpenLibraryError> {
-        let client = ClientBuilder::new()
-            .build()
-            .map_err(|error| OpenLibraryError::ClientBuildingError { source: error })?;
-
-        let host_url = match host {
-            Some(value) => value,
-            None => Url::parse("https://openlibrary.org/").unwrap(),
-        };
+// Implemented by hand since deriving would require the state marker to be `Clone`
+impl<A> Clone for OpenLibraryClient<A> {
+    fn clone(&self) -> Self {
+        Self::new(self.client.clone(), self.host.clone())
+    }
+}
 
-        Ok(Self {
-            account: AccountClient {
-                client,
-                host: host_url,
-            },
-        })
+impl OpenLibraryClient {
+    pub fn builder() -> OpenLibraryClientBuilder {
+        OpenLibraryClientBuilder::new()
     }
 
+    /// Logs in, returning an authenticated client which shares this client's configuration.
     pub async fn login(
         &self,
         username: String,
         password: String,
-    ) -> Result<Session, OpenLibraryError> {
-        self.account.login(username, password).await
+    ) -> Result<OpenLibraryClient<Authenticated>, OpenLibraryError> {
+        let session = self.account.login(username, password).await?;
+
+        Ok(OpenLibraryClient::new(
+            self.client.clone().with_session(session, &self.host),
+            self.host.clone(),
+        ))
+    }
+
+    /// Treats a client built with a session, or credentials to log in with, as authenticated. The
+    /// session is only checked once a request requires it.
+    pub fn authenticated(&self) -> Result<OpenLibraryClient<Authenticated>, OpenLibraryError> {
+        if !self.client.can_authenticate() {
+            return Err(OpenLibraryError::NotAuthenticated {
+                reason: "The client was built without a session or credentials".to_string(),
+            });
+        }
+
+        Ok(OpenLibraryClient::new(
+            self.client.clone(),
+            self.host.clone(),
+        ))
     }
 }
 
-#[derive(Clone)]
-pub struct OpenLibraryClient {
-    pub account: AccountClient,
-    pub author: AuthorClient,
-    pub books: BooksClient,
-    pub works: WorksClient,
+impl OpenLibraryClient<Authenticated> {
+    /// Logs out, returning an anonymous client which shares this client's configuration but
+    /// neither sends a session nor logs in again.
+    pub async fn logout(self) -> Result<OpenLibraryClient, OpenLibraryError> {
+        self.account.logout().await?;
+
+        Ok(OpenLibraryClient::new(
+            self.client.with_authenticator(None),
+            self.host,
+        ))
+    }
 }
 
-impl OpenLibraryClient {
-    pub fn builder() -> OpenLibraryClientBuilder {
-        OpenLibraryClientBuilder::new()
+impl<A> OpenLibraryClient<A> {
+    fn new(client: HttpClient, host: Url) -> OpenLibraryClient<A> {
+        OpenLibraryClient {
+            books: BooksClient::new(&client, &host),
+            account: AccountClient::new(&client, &host),
+            author: AuthorClient::new(&client, &host),
+            works: WorksClient::new(&client, &host),
+            client,
+            host,
+        }
+    }
+
+    /// Returns a client sharing this client's configuration whose calls use the given cache mode.
+    pub fn with_cache_mode(&self, cache_mode: CacheMode) -> OpenLibraryClient<A> {
+        Self::new(
+            self.client.clone().with_cache_mode(cache_mode),
+            self.host.clone(),
+        )
+    }
+
+    /// Returns a client sharing this client's configuration whose calls time out after the given
+    /// duration instead of the configured total timeout.
+    pub fn with_timeout(&self, timeout: Duration) -> OpenLibraryClient<A> {
+        Self::new(
+            self.client.clone().with_timeout(Some(timeout)),
+            self.host.clone(),
+        )
+    }
+
+    /// Returns a client sharing this client's configuration whose calls map responses onto models
+    /// using the given mode.
+    pub fn with_deserialization_mode(&self, mode: DeserializationMode) -> OpenLibraryClient<A> {
+        Self::new(
+            self.client.clone().with_deserialization_mode(mode),
+            self.host.clone(),
+        )
+    }
+
+    /// The session currently used by this client, which may have been replaced after logging in
+    /// again with the configured credentials.
+    pub fn session(&self) -> Option<Session> {
+        self.client.session()
+    }
+
+    pub fn cache_stats(&self) -> Option<CacheStats> {
+        self.client.cache_stats()
+    }
+
+    /// The health of each host, starting with the primary, when failover hosts are configured.
+    pub fn host_health(&self) -> Option<Vec<HostHealth>> {
+        self.client.host_health()
+    }
+
+    /// The client's request pipeline as a [`tower::Service`], for sending requests of your own
+    /// with the client's session, headers, retries, rate limiting and layers applied.
+    #[cfg(feature = "tower")]
+    pub fn service(&self) -> service::OpenLibraryService {
+        service::OpenLibraryService::new(self.client.clone())
     }
 }
 
 pub struct OpenLibraryClientBuilder {
     host: Url,
+    hosts: Vec<Url>,
+    failover_policy: FailoverPolicy,
     session: Option<Session>,
+    credentials_provider: Option<Arc<dyn CredentialsProvider>>,
+    retry_policy: RetryPolicy,
+    rate_limit: Option<RateLimit>,
+    covers_rate_limit: Option<RateLimit>,
+    rate_limit_mode: RateLimitMode,
+    application: Option<(String, String)>,
+    contact: Option<String>,
+    default_headers: HeaderMap,
+    cache: Option<CacheConfig>,
+    conditional_requests: bool,
+    transport: Option<Arc<dyn HttpTransport>>,
+    interceptors: Vec<Arc<dyn Interceptor>>,
+    #[cfg(feature = "tower")]
+    layers: Vec<service::LayerFn>,
+    cassette: Option<Cassette>,
+    request_coalescing: bool,
+    error_body_capture: Option<usize>,
+    deserialization_mode: DeserializationMode,
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    proxy: Option<Url>,
+    proxy_credentials: Option<(String, String)>,
+    root_certificates: Vec<Vec<u8>>,
+    pool_max_idle_per_host: Option<usize>,
+    pool_idle_timeout: Option<Duration>,
 }
 
 impl OpenLibraryClientBuilder {
     fn new() -> OpenLibraryClientBuilder {
         OpenLibraryClientBuilder {
             host: Url::parse("https://openlibrary.org/").unwrap(),
+            hosts: Vec::new(),
+            failover_policy: FailoverPolicy::default(),
             session: None,
+            credentials_provider: None,
+            retry_policy: RetryPolicy::none(),
+            rate_limit: None,
+            covers_rate_limit: None,
+            rate_limit_mode: RateLimitMode::Wait,
+            application: None,
+            contact: None,
+            default_headers: HeaderMap::new(),
+            cache: None,
+            conditional_requests: false,
+            transport: None,
+            interceptors: Vec::new(),
+            #[cfg(feature = "tower")]
+            layers: Vec::new(),
+            cassette: None,
+            request_coalescing: false,
+            error_body_capture: None,
+            deserialization_mode: DeserializationMode::Strict,
+            connect_timeout: None,
+            read_timeout: None,
+            timeout: None,
+            proxy: None,
+            proxy_credentials: None,
+            root_certificates: Vec::new(),
+            pool_max_idle_per_host: None,
+            pool_idle_timeout: None,
         }
     }
 
     pub fn with_host(self, host: Url) -> OpenLibraryClientBuilder {
+        OpenLibraryClientBuilder { host, ..self }
+    }
+
+    /// Serves reads from the first healthy host in the list, e.g. a mirror followed by
+    /// openlibrary.org, falling back to the next one when a host fails or is missing the
+    /// resource. Writes and account requests always go to the primary host set with
+    /// [`OpenLibraryClientBuilder::with_host`]. A host's path is kept as a prefix of every path
+    /// requested from it, e.g. `https://mirror.example/openlibrary`.
+    pub fn with_hosts(self, hosts: Vec<Url>) -> OpenLibraryClientBuilder {
+        // Without a trailing slash joining paths onto the host would replace its last segment
+        let hosts = hosts
+            .into_iter()
+            .map(|mut host| {
+                if !host.path().ends_with('/') {
+                    host.set_path(format!("{}/", host.path()).as_str());
+                }
+                host
+            })
+            .collect();
+
+        OpenLibraryClientBuilder { hosts, ..self }
+    }
+
+    pub fn with_failover_policy(self, failover_policy: FailoverPolicy) -> OpenLibraryClientBuilder {
         OpenLibraryClientBuilder {
-            host,
-            session: self.session,
+            failover_policy,
+            ..self
         }
     }
 
     pub fn with_session(self, session: &Session) -> OpenLibraryClientBuilder {
         OpenLibraryClientBuilder {
-            host: self.host,
             session: Some(session.clone()),
+            ..self
+        }
+    }
+
+    /// Logs in with the provided credentials, and replays the request once, whenever the API
+    /// rejects the session, e.g. because it expired.
+    pub fn with_credentials_provider<P>(self, provider: P) -> OpenLibraryClientBuilder
+    where
+        P: CredentialsProvider + 'static,
+    {
+        OpenLibraryClientBuilder {
+            credentials_provider: Some(Arc::new(provider)),
+            ..self
+        }
+    }
+
+    pub fn with_retry_policy(self, retry_policy: RetryPolicy) -> OpenLibraryClientBuilder {
+        OpenLibraryClientBuilder {
+            retry_policy,
+            ..self
+        }
+    }
+
+    pub fn with_rate_limit(self, rate_limit: RateLimit) -> OpenLibraryClientBuilder {
+        OpenLibraryClientBuilder {
+            rate_limit: Some(rate_limit),
+            ..self
+        }
+    }
+
+    pub fn with_covers_rate_limit(self, rate_limit: RateLimit) -> OpenLibraryClientBuilder {
+        OpenLibraryClientBuilder {
+            covers_rate_limit: Some(rate_limit),
+            ..self
+        }
+    }
+
+    pub fn with_rate_limit_mode(self, rate_limit_mode: RateLimitMode) -> OpenLibraryClientBuilder {
+        OpenLibraryClientBuilder {
+            rate_limit_mode,
+            ..self
+        }
+    }
+
+    pub fn with_application(self, name: &str, version: &str) -> OpenLibraryClientBuilder {
+        OpenLibraryClientBuilder {
+            application: Some((name.to_string(), version.to_string())),
+            ..self
+        }
+    }
+
+    pub fn with_contact(self, email: &str) -> OpenLibraryClientBuilder {
+        OpenLibraryClientBuilder {
+            contact: Some(email.to_string()),
+            ..self
+        }
+    }
+
+    pub fn with_default_headers(self, headers: HeaderMap) -> OpenLibraryClientBuilder {
+        let mut default_headers = self.default_headers;
+        default_headers.extend(headers);
+
+        OpenLibraryClientBuilder {
+            default_headers,
+            ..self
+        }
+    }
+
+    pub fn with_cache(self, cache: CacheConfig) -> OpenLibraryClientBuilder {
+        OpenLibraryClientBuilder {
+            cache: Some(cache),
+            ..self
+        }
+    }
+
+    /// Revalidates previously seen responses with `If-None-Match` / `If-Modified-Since`, serving
+    /// the stored copy when the server answers `304 Not Modified`.
+    pub fn with_conditional_requests(self, conditional_requests: bool) -> OpenLibraryClientBuilder {
+        OpenLibraryClientBuilder {
+            conditional_requests,
+            ..self
+        }
+    }
+
+    /// Replaces the reqwest based transport used to send requests, e.g. to route them through an
+    /// existing HTTP stack or to serve canned responses in tests.
+    pub fn with_transport<T>(self, transport: T) -> OpenLibraryClientBuilder
+    where
+        T: HttpTransport + 'static,
+    {
+        OpenLibraryClientBuilder {
+            transport: Some(Arc::new(transport)),
+            ..self
+        }
+    }
+
+    /// Adds an interceptor to the end of the chain applied to every request and response.
+    pub fn with_interceptor<I>(self, interceptor: I) -> OpenLibraryClientBuilder
+    where
+        I: Interceptor + 'static,
+    {
+        let mut interceptors = self.interceptors;
+        interceptors.push(Arc::new(interceptor));
+
+        OpenLibraryClientBuilder {
+            interceptors,
+            ..self
+        }
+    }
+
+    /// Wraps every request sent over the network in a [`tower::Layer`], e.g. for load shedding or
+    /// concurrency limits. Layers sit inside the client's own retries and rate limiting, so each
+    /// attempt passes through them, and the first layer added is the outermost.
+    #[cfg(feature = "tower")]
+    pub fn with_layer<L>(self, layer: L) -> OpenLibraryClientBuilder
+    where
+        L: tower::Layer<service::BoxHttpService> + Send + Sync + 'static,
+        L::Service: tower::Service<transport::HttpRequest, Response = HttpResponse>
+            + Clone
+            + Send
+            + 'static,
+        <L::Service as tower::Service<transport::HttpRequest>>::Error: Into<tower::BoxError>,
+        <L::Service as tower::Service<transport::HttpRequest>>::Future: Send + 'static,
+    {
+        let mut layers = self.layers;
+        layers.push(service::layer_fn(layer));
+
+        OpenLibraryClientBuilder { layers, ..self }
+    }
+
+    /// Records every exchange with the API to, or replays them from, a cassette file.
+    pub fn with_cassette(self, cassette: Cassette) -> OpenLibraryClientBuilder {
+        OpenLibraryClientBuilder {
+            cassette: Some(cassette),
+            ..self
+        }
+    }
+
+    /// Shares a single network request, and the model parsed from its response, between concurrent
+    /// identical GET requests, including those made through clones of the built client. Callers
+    /// sharing a failure each receive it as [`OpenLibraryError::CoalescedRequestFailed`].
+    pub fn with_request_coalescing(self, request_coalescing: bool) -> OpenLibraryClientBuilder {
+        OpenLibraryClientBuilder {
+            request_coalescing,
+            ..self
+        }
+    }
+
+    /// Attaches up to `max_bytes` of the response body to deserialization errors.
+    pub fn with_error_body_capture(self, max_bytes: usize) -> OpenLibraryClientBuilder {
+        OpenLibraryClientBuilder {
+            error_body_capture: Some(max_bytes),
+            ..self
+        }
+    }
+
+    pub fn with_deserialization_mode(self, mode: DeserializationMode) -> OpenLibraryClientBuilder {
+        OpenLibraryClientBuilder {
+            deserialization_mode: mode,
+            ..self
+        }
+    }
+
+    // The connection options below only apply to the default reqwest transport
+
+    pub fn with_connect_timeout(self, connect_timeout: Duration) -> OpenLibraryClientBuilder {
+        OpenLibraryClientBuilder {
+            connect_timeout: Some(connect_timeout),
+            ..self
+        }
+    }
+
+    /// Fails a request when the server stalls for longer than this while sending the response
+    /// headers or body.
+    pub fn with_read_timeout(self, read_timeout: Duration) -> OpenLibraryClientBuilder {
+        OpenLibraryClientBuilder {
+            read_timeout: Some(read_timeout),
+            ..self
+        }
+    }
+
+    /// Bounds each attempt from connecting through to reading the whole body. Use
+    /// [`OpenLibraryClient::with_timeout`] to change it for individual calls.
+    pub fn with_timeout(self, timeout: Duration) -> OpenLibraryClientBuilder {
+        OpenLibraryClientBuilder {
+            timeout: Some(timeout),
+            ..self
+        }
+    }
+
+    /// Sends both HTTP and HTTPS requests through the given proxy.
+    pub fn with_proxy(self, proxy: Url) -> OpenLibraryClientBuilder {
+        OpenLibraryClientBuilder {
+            proxy: Some(proxy),
+            ..self
+        }
+    }
+
+    pub fn with_proxy_credentials(
+        self,
+        username: &str,
+        password: &str,
+    ) -> OpenLibraryClientBuilder {
+        OpenLibraryClientBuilder {
+            proxy_credentials: Some((username.to_string(), password.to_string())),
+            ..self
+        }
+    }
+
+    /// Trusts a PEM encoded certificate in addition to the system's root certificates.
+    pub fn with_root_certificate(self, pem: &[u8]) -> OpenLibraryClientBuilder {
+        let mut root_certificates = self.root_certificates;
+        root_certificates.push(pem.to_vec());
+
+        OpenLibraryClientBuilder {
+            root_certificates,
+            ..self
+        }
+    }
+
+    pub fn with_pool_max_idle_per_host(self, max_idle: usize) -> OpenLibraryClientBuilder {
+        OpenLibraryClientBuilder {
+            pool_max_idle_per_host: Some(max_idle),
+            ..self
+        }
+    }
+
+    pub fn with_pool_idle_timeout(self, idle_timeout: Duration) -> OpenLibraryClientBuilder {
+        OpenLibraryClientBuilder {
+            pool_idle_timeout: Some(idle_timeout),
+            ..self
         }
     }
 
     pub fn build(self) -> Result<OpenLibraryClient, OpenLibraryError> {
-        let default_headers = match self.session {
-            Some(session) => {
-                let mut headers = HeaderMap::new();
-                headers.insert(
-                    "Cookie",
-                    HeaderValue::from_str(session.cookie().as_str()).map_err(|_error| {
-                        OpenLibraryError::ParsingError {
-                            reason: "Unable to parse session cookie into header value".to_string(),
-                        }
-                    })?,
-                );
-                headers
+        Ok(OpenLibraryClient::new(self.http_client()?, self.host))
+    }
+
+    #[cfg(feature = "blocking")]
+    pub fn build_blocking(self) -> Result<blocking::OpenLibraryClient, OpenLibraryError> {
+        blocking::OpenLibraryClient::from(self.build()?)
+    }
+
+    fn http_client(&self) -> Result<HttpClient, OpenLibraryError> {
+        let transport: Arc<dyn HttpTransport> = match &self.transport {
+            Some(transport) => transport.clone(),
+            None => Arc::new(self.reqwest_transport()?),
+        };
+        let host_pool = match self.hosts.is_empty() {
+            true => None,
+            false => Some(HostPool::new(
+                self.host.clone(),
+                self.hosts.clone(),
+                self.failover_policy.clone(),
+            )),
+        };
+        let transport: Arc<dyn HttpTransport> = match &host_pool {
+            Some(pool) => Arc::new(FailoverTransport::new(pool.clone(), transport)),
+            None => transport,
+        };
+        #[cfg(feature = "tower")]
+        let transport: Arc<dyn HttpTransport> = match self.layers.is_empty() {
+            true => transport,
+            false => {
+                let inner = tower::util::BoxCloneService::new(tower::ServiceExt::map_err(
+                    service::TransportService::from_arc(transport),
+                    tower::BoxError::from,
+                ));
+                let stack = self
+                    .layers
+                    .iter()
+                    .rev()
+                    .fold(inner, |inner, layer| layer(inner));
+                Arc::new(service::ServiceTransport::new(stack))
             }
-            None => HeaderMap::new(),
         };
+        let transport: Arc<dyn HttpTransport> = match &self.cassette {
+            Some(cassette) => Arc::new(CassetteTransport::new(cassette.clone(), transport)?),
+            None => transport,
+        };
+
+        Ok(HttpClient::new(transport)
+            .with_default_headers(self.headers()?)
+            .with_interceptors(self.interceptors.clone())
+            .with_retry_policy(self.retry_policy.clone())
+            .with_host_pool(host_pool)
+            .with_error_body_capture(self.error_body_capture)
+            .with_deserialization_mode(self.deserialization_mode)
+            .with_authenticator(match (&self.session, &self.credentials_provider) {
+                (None, None) => None,
+                (session, provider) => Some(Authenticator::new(
+                    session.clone(),
+                    provider.clone(),
+                    self.host.clone(),
+                )),
+            })
+            .with_coalescer(match self.request_coalescing {
+                true => Some(Coalescer::default()),
+                false => None,
+            })
+            .with_rate_limiter(RateLimiter::new(
+                self.rate_limit,
+                self.covers_rate_limit,
+                self.rate_limit_mode,
+            ))
+            .with_cache(match (&self.cache, self.conditional_requests) {
+                (Some(cache), conditional) => Some(ResponseCache::new(cache.clone(), conditional)),
+                (None, true) => Some(ResponseCache::conditional_only()),
+                (None, false) => None,
+            }))
+    }
 
-        let client = ClientBuilder::new()
-            .default_headers(default_headers)
-            .build()
-            .map_err(|error| OpenLibraryError::ClientBuildingError { source: error })?;
+    fn reqwest_transport(&self) -> Result<ReqwestTransport, OpenLibraryError> {
+        let building_error = |error| OpenLibraryError::ClientBuildingError { source: error };
+        let mut builder = ClientBuilder::new();
 
-        Ok(OpenLibraryClient {
-            books: BooksClient::new(&client, &self.host),
-            account: AccountClient::new(&client, &self.host),
-            author: AuthorClient::new(&client, &self.host),
-            works: WorksClient::new(&client, &self.host),
+        if let Some(connect_timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(connect_timeout);
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(url) = &self.proxy {
+            let mut proxy = Proxy::all(url.clone()).map_err(building_error)?;
+            if let Some((username, password)) = &self.proxy_credentials {
+                proxy = proxy.basic_auth(username, password);
+            }
+            builder = builder.proxy(proxy);
+        }
+        for pem in &self.root_certificates {
+            builder =
+                builder.add_root_certificate(Certificate::from_pem(pem).map_err(building_error)?);
+        }
+        if let Some(max_idle) = self.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max_idle);
+        }
+        if let Some(idle_timeout) = self.pool_idle_timeout {
+            builder = builder.pool_idle_timeout(idle_timeout);
+        }
+
+        let transport = ReqwestTransport::new(builder.build().map_err(building_error)?);
+        Ok(match self.read_timeout {
+            Some(read_timeout) => transport.with_read_timeout(read_timeout),
+            None => transport,
         })
     }
+
+    fn headers(&self) -> Result<HeaderMap, OpenLibraryError> {
+        let mut headers = self.default_headers.clone();
+
+        if !headers.contains_key(USER_AGENT) {
+            headers.insert(
+                USER_AGENT,
+                HeaderValue::from_str(self.user_agent().as_str()).map_err(|_error| {
+                    OpenLibraryError::ParsingError {
+                        reason: "Unable to parse application details into a User-Agent header"
+                            .to_string(),
+                    }
+                })?,
+            );
+        }
+
+        Ok(headers)
+    }
+
+    // Open Library asks heavy users to identify their application along with a way to contact them
+    // e.g. `MyApplication/1.0 (me@example.com) open-library/0.7.1`
+    fn user_agent(&self) -> String {
+        let library = format!("{}/{}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+
+        match (&self.application, &self.contact) {
+            (Some((name, version)), Some(contact)) => {
+                format!("{}/{} ({}) {}", name, version, contact, library)
+            }
+            (Some((name, version)), None) => format!("{}/{} {}", name, version, library),
+            (None, Some(contact)) => format!("{} ({})", library, contact),
+            (None, None) => library,
+        }
+    }
 }
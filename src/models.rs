@@ -8,6 +8,7 @@ pub mod account;
 pub mod authors;
 pub mod books;
 pub mod identifiers;
+pub(crate) mod lenient;
 pub mod works;
 
 #[cfg(test)]
@@ -56,7 +57,7 @@ impl<'de> Deserialize<'de> for OpenLibraryResource {
             .filter(|str| !str.is_empty())
             .collect::<Vec<&str>>();
 
-        let resource = match chunks.get(0) {
+        let resource = match chunks.first() {
             Some(string) => Ok(*string),
             None => Err(D::Error::custom(format!(
                 "Supplied identifier string has improper format {}",
@@ -114,3 +115,23 @@ pub enum LinkName {
 }
 
 pub trait OpenLibraryModel {}
+
+impl OpenLibraryModel for serde_json::Value {}
+
+/// A typed model along with the raw document it was parsed from, for reading fields the model
+/// doesn't cover.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WithRaw<T> {
+    pub model: T,
+    pub raw: serde_json::Value,
+}
+
+/// How responses are mapped onto models. In `Lenient` mode a malformed optional field becomes
+/// `None`, with a warning logged and its value kept in the model's `extra` fields, rather than
+/// failing the whole response.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum DeserializationMode {
+    #[default]
+    Strict,
+    Lenient,
+}
@@ -1,8 +1,7 @@
 use crate::models::OpenLibraryResource;
-use crate::{OpenLibraryClient, OpenLibraryError, OpenLibraryErrorResponse};
-use chrono::{DateTime, Utc};
-use serde::de::Error;
-use serde::{Deserialize, Deserializer, Serialize};
+use crate::{OpenLibraryClient, OpenLibraryError};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize, Serializer};
 
 #[derive(Serialize)]
 pub struct LoginRequest {
@@ -10,47 +9,174 @@ pub struct LoginRequest {
     pub password: String,
 }
 
-pub enum ReadingLogResponseWrapper {
-    Success(ReadingLogResponse),
-    Err(OpenLibraryErrorResponse),
-}
-
-impl<'de> Deserialize<'de> for ReadingLogResponseWrapper {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        let json: serde_json::Value = Deserialize::deserialize(deserializer)?;
-
-        match json.get("error") {
-            None => Ok(ReadingLogResponseWrapper::Success(
-                serde_json::from_value(json).map_err(D::Error::custom)?,
-            )),
-            Some(_) => Ok(ReadingLogResponseWrapper::Err(
-                serde_json::from_value(json).map_err(D::Error::custom)?,
-            )),
-        }
-    }
-}
-
-#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+/// An Open Library login, holding the session cookie without its attributes along with when the
+/// cookie expires, if the server said so.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct Session {
     cookie: String,
     username: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    expires_at: Option<DateTime<Utc>>,
 }
 
 impl Session {
+    /// Parses a `Set-Cookie` header value, e.g. `session=...; Path=/; Expires=...`, keeping the
+    /// `name=value` pair to send back and the expiry given by its `Max-Age` or `Expires` attribute.
     pub fn from(cookie: String, username: String) -> Self {
-        Session { cookie, username }
+        let mut attributes = cookie.split(';').map(str::trim);
+        let pair = attributes.next().unwrap_or_default().to_string();
+
+        let mut expires = None;
+        let mut max_age = None;
+        for attribute in attributes {
+            let (name, value) = attribute.split_once('=').unwrap_or((attribute, ""));
+            if name.eq_ignore_ascii_case("expires") {
+                expires = parse_http_date(value.trim());
+            } else if name.eq_ignore_ascii_case("max-age") {
+                max_age = value.trim().parse::<i64>().ok();
+            }
+        }
+
+        // Max-Age takes precedence over Expires when both are present
+        let expires_at = match max_age {
+            Some(seconds) => Some(
+                chrono::Duration::try_seconds(seconds)
+                    .and_then(|max_age| Utc::now().checked_add_signed(max_age))
+                    .unwrap_or(DateTime::<Utc>::MAX_UTC),
+            ),
+            None => expires,
+        };
+
+        Session {
+            cookie: pair,
+            username,
+            expires_at,
+        }
     }
 
+    /// The `name=value` pair sent in the `Cookie` header of authenticated requests.
     pub fn cookie(&self) -> &String {
         &self.cookie
     }
 
+    pub fn name(&self) -> &str {
+        self.cookie
+            .split_once('=')
+            .map_or("", |(name, _)| name.trim())
+    }
+
+    pub fn value(&self) -> &str {
+        self.cookie
+            .split_once('=')
+            .map_or(self.cookie.as_str(), |(_, value)| value.trim())
+    }
+
     pub fn username(&self) -> &String {
         &self.username
     }
+
+    /// When the cookie expires, `None` for a cookie which lasts until the session ends.
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expires_at
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| expires_at <= Utc::now())
+    }
+}
+
+// Accepts the preferred IMF-fixdate format as well as the obsolete RFC 850 and asctime formats
+fn parse_http_date(value: &str) -> Option<DateTime<Utc>> {
+    if let Ok(date) = DateTime::parse_from_rfc2822(value) {
+        return Some(date.with_timezone(&Utc));
+    }
+
+    [
+        "%A, %d-%b-%y %H:%M:%S GMT",
+        "%a, %d-%b-%Y %H:%M:%S GMT",
+        "%a %b %e %H:%M:%S %Y",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+    .map(|date| date.and_utc())
+}
+
+#[derive(Serialize)]
+pub(crate) struct BookshelfRequest {
+    pub action: &'static str,
+    pub bookshelf_id: u8,
+    pub redir: bool,
+}
+
+#[derive(Serialize)]
+pub(crate) struct RatingRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rating: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<&'static str>,
+    pub redir: bool,
+}
+
+/// A list to create with `create_list` on the [`account`](crate::OpenLibraryClient::account) of an
+/// [`Authenticated`](crate::auth::Authenticated) client.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct NewList {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(serialize_with = "serialize_seeds")]
+    pub seeds: Vec<OpenLibraryResource>,
+}
+
+impl NewList {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_description(self, description: &str) -> Self {
+        Self {
+            description: Some(description.to_string()),
+            ..self
+        }
+    }
+
+    pub fn with_seed(mut self, seed: OpenLibraryResource) -> Self {
+        self.seeds.push(seed);
+        self
+    }
+}
+
+#[derive(Serialize)]
+pub(crate) struct ListSeedsRequest<'a> {
+    #[serde(serialize_with = "serialize_seeds")]
+    pub add: &'a [OpenLibraryResource],
+    #[serde(serialize_with = "serialize_seeds")]
+    pub remove: &'a [OpenLibraryResource],
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ListResponse {
+    // e.g. `/people/username/lists/OL1L`
+    pub key: String,
+}
+
+// Lists refer to their entries, or seeds, as `{"key": "/works/OL1W"}`
+fn serialize_seeds<S>(seeds: &[OpenLibraryResource], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    #[derive(Serialize)]
+    struct Seed<'a> {
+        key: &'a OpenLibraryResource,
+    }
+
+    serializer.collect_seq(seeds.iter().map(|key| Seed { key }))
 }
 
 #[derive(Clone)]
@@ -69,9 +195,18 @@ impl ReadingLog {
         }
     }
 
-    pub async fn retrieve_for(
+    /// The identifier Open Library uses for the shelf when editing a reading log.
+    pub fn bookshelf_id(&self) -> u8 {
+        match self {
+            ReadingLog::WantToRead => 1,
+            ReadingLog::CurrentlyReading => 2,
+            ReadingLog::AlreadyRead => 3,
+        }
+    }
+
+    pub async fn retrieve_for<A>(
         &self,
-        client: &OpenLibraryClient,
+        client: &OpenLibraryClient<A>,
         username: String,
     ) -> Result<Vec<ReadingLogEntry>, OpenLibraryError> {
         match self {
@@ -82,7 +217,7 @@ impl ReadingLog {
     }
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ReadingLogResponse {
     pub page: i16,
     pub reading_log_entries: Vec<ReadingLogEntry>,
@@ -1,11 +1,13 @@
 use crate::format::KeyedValue;
 use crate::models::identifiers::OpenLibraryIdentifier;
+use crate::models::lenient::{self, lenient_field};
 use crate::models::works::Work;
 use crate::models::{Link, LinkName, OpenLibraryModel, OpenLibraryResource};
 use crate::OpenLibraryError;
 use chrono::NaiveDateTime;
 use serde::de::Error;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::convert::TryFrom;
@@ -14,7 +16,7 @@ use std::fmt::Display;
 use std::str::FromStr;
 use url::Url;
 
-#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct Author {
     pub key: String,
     #[serde(default)]
@@ -34,7 +36,7 @@ pub struct Author {
     pub _version_: u64,
 }
 
-#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct AuthorReference {
     #[serde(rename = "type")]
     #[serde(deserialize_with = "deserialize_author_type")]
@@ -43,7 +45,7 @@ pub struct AuthorReference {
     pub identifier: KeyedValue<OpenLibraryResource>,
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub enum AuthorType {
     AuthorRole,
 }
@@ -81,9 +83,9 @@ impl<'de> Deserialize<'de> for AuthorType {
             .filter(|str| !str.is_empty())
             .collect::<Vec<&str>>();
 
-        match chunks.get(0) {
+        match chunks.first() {
             Some(&"type") => match chunks.get(1) {
-                Some(value) => Ok(AuthorType::from_str(*value).map_err(D::Error::custom)?),
+                Some(value) => Ok(AuthorType::from_str(value).map_err(D::Error::custom)?),
                 None => Err(D::Error::custom("No Author Type was provided!")),
             },
             _ => Err(D::Error::custom(format!(
@@ -103,38 +105,71 @@ impl Serialize for AuthorType {
     }
 }
 
-#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct AuthorDetails {
+    #[serde(default, deserialize_with = "lenient_title")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub title: Option<String>,
     #[serde(default)]
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub source_records: Vec<String>, //TODO parse records
     pub key: OpenLibraryResource,
-    #[serde(default)]
-    #[serde(with = "crate::format::value")]
+    #[serde(default, deserialize_with = "lenient_bio")]
+    #[serde(serialize_with = "crate::format::value::serialize")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub bio: Option<String>,
     pub photos: Vec<i32>,
-    // #[serde(with = "crate::format::date")]
     pub birth_date: String,
     pub personal_name: String,
     pub remote_ids: HashMap<String, String>,
+    #[serde(default, deserialize_with = "lenient_entity_type")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub entity_type: Option<String>, //TODO: should be enum
     pub links: Vec<Link>,
     pub name: String,
     pub alternate_names: Vec<String>,
+    #[serde(default, deserialize_with = "lenient_wikipedia")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub wikipedia: Option<Url>,
     pub latest_revision: u16,
     pub revision: u16,
-    #[serde(with = "crate::format::value")]
+    #[serde(default, deserialize_with = "lenient_created")]
+    #[serde(serialize_with = "crate::format::value::serialize")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub created: Option<NaiveDateTime>,
-    #[serde(with = "crate::format::value")]
+    #[serde(default, deserialize_with = "lenient_last_modified")]
+    #[serde(serialize_with = "crate::format::value::serialize")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub last_modified: Option<NaiveDateTime>,
+    /// Fields this client doesn't model, kept so re-serializing an author doesn't lose them.
+    #[serde(flatten, deserialize_with = "lenient::extra")]
+    pub extra: Map<String, Value>,
 }
 
 impl OpenLibraryModel for AuthorDetails {}
 
+lenient_field!(lenient_title, "title", Option<String>);
+lenient_field!(
+    lenient_bio,
+    "bio",
+    Option<String>,
+    crate::format::value::deserialize
+);
+lenient_field!(lenient_entity_type, "entity_type", Option<String>);
+lenient_field!(lenient_wikipedia, "wikipedia", Option<Url>);
+lenient_field!(
+    lenient_created,
+    "created",
+    Option<NaiveDateTime>,
+    crate::format::value::deserialize
+);
+lenient_field!(
+    lenient_last_modified,
+    "last_modified",
+    Option<NaiveDateTime>,
+    crate::format::value::deserialize
+);
+
 #[derive(Deserialize, Debug, Eq, PartialEq, Serialize)]
 pub struct AuthorWorksRequest {
     pub identifier: OpenLibraryIdentifier,
@@ -201,13 +236,13 @@ impl TryFrom<Url> for AuthorWorksRequest {
 
         Ok(Self {
             identifier: OpenLibraryIdentifier::from_str(result)?,
-            limit: limit,
-            offset: offset,
+            limit,
+            offset,
         })
     }
 }
 
-#[derive(Deserialize, Debug, Eq, PartialEq, Serialize)]
+#[derive(Clone, Deserialize, Debug, Eq, PartialEq, Serialize)]
 pub struct AuthorWorksResponse {
     #[serde(default)]
     #[serde(skip_serializing_if = "HashMap::is_empty")]
@@ -220,7 +255,7 @@ pub struct AuthorWorksResponse {
 
 impl OpenLibraryModel for AuthorWorksResponse {}
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct AuthorResponse {
     #[serde(rename = "numFound")]
     pub num_found: i32,
@@ -1,7 +1,9 @@
+use crate::models::lenient::{self, lenient_field};
 use crate::models::{Link, OpenLibraryModel, OpenLibraryResource};
 use crate::OpenLibraryError;
 use serde::de::Error;
 use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 use std::str::FromStr;
@@ -15,10 +17,17 @@ pub struct Book {
     pub url: Vec<Url>,
     pub key: OpenLibraryResource,
     pub title: String,
+    #[serde(default, deserialize_with = "lenient_subtitle")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub subtitle: Option<String>,
+    #[serde(default, deserialize_with = "lenient_pagination")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub pagination: Option<String>,
+    #[serde(default, deserialize_with = "lenient_by_statement")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub by_statement: Option<String>,
+    #[serde(default, deserialize_with = "lenient_notes")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub notes: Option<String>,
     #[serde(default)]
     #[serde(skip_serializing_if = "Vec::is_empty")]
@@ -49,7 +58,9 @@ pub struct Book {
     #[serde(deserialize_with = "strings_or_entities")]
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub publish_places: Vec<String>,
-    pub publish_date: String,
+    #[serde(default, deserialize_with = "lenient_publish_date")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publish_date: Option<String>,
     #[serde(default)]
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub excerpts: Vec<Excerpt>,
@@ -62,13 +73,27 @@ pub struct Book {
     #[serde(default)]
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub ebooks: Vec<ElectronicBook>,
+    #[serde(default, deserialize_with = "lenient_number_of_pages")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub number_of_pages: Option<u32>,
+    #[serde(default, deserialize_with = "lenient_weight")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub weight: Option<String>,
+    /// Fields this client doesn't model, kept so re-serializing a book doesn't lose them.
+    #[serde(flatten, deserialize_with = "lenient::extra")]
+    pub extra: Map<String, Value>,
 }
 
 impl OpenLibraryModel for Book {}
 
+lenient_field!(lenient_subtitle, "subtitle", Option<String>);
+lenient_field!(lenient_pagination, "pagination", Option<String>);
+lenient_field!(lenient_by_statement, "by_statement", Option<String>);
+lenient_field!(lenient_notes, "notes", Option<String>);
+lenient_field!(lenient_publish_date, "publish_date", Option<String>);
+lenient_field!(lenient_number_of_pages, "number_of_pages", Option<u32>);
+lenient_field!(lenient_weight, "weight", Option<String>);
+
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub enum BibliographyKey {
     ISBN(String),
@@ -119,7 +144,7 @@ impl<'de> serde::Deserialize<'de> for BibliographyKey {
             return Err(D::Error::custom("The specified value {} has improper form"));
         }
 
-        let key = match chunks.get(0) {
+        let key = match chunks.first() {
             Some(value) => Ok(*value),
             None => Err(D::Error::custom(format!(
                 "Supplied identifier string has improper format {}",
@@ -247,10 +272,13 @@ pub struct Entity {
 #[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 pub struct Classifications {
     #[serde(default)]
-    #[serde(rename(deserialize = "dewey_decimal_class"))]
+    #[serde(rename(deserialize = "dewey_decimal_class"), alias = "dewey_decimal")]
     pub dewey_decimal: Vec<String>,
     #[serde(default)]
-    #[serde(rename(deserialize = "lc_classifications"))]
+    #[serde(
+        rename(deserialize = "lc_classifications"),
+        alias = "library_of_congress"
+    )]
     pub library_of_congress: Vec<String>,
 }
 
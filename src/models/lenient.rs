@@ -0,0 +1,96 @@
+use crate::models::DeserializationMode;
+use serde::{Deserialize, Deserializer};
+use serde_json::{Map, Value};
+use std::cell::{Cell, RefCell};
+
+// Responses are parsed synchronously on a single thread so the mode, and any values set aside
+// while parsing, can live in thread locals for the derived implementations to consult
+thread_local! {
+    static MODE: Cell<DeserializationMode> = const { Cell::new(DeserializationMode::Strict) };
+    static MALFORMED: RefCell<Vec<(&'static str, Value)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Applies the given mode to every model deserialized until the guard is dropped.
+pub(crate) fn with_mode(mode: DeserializationMode) -> ModeGuard {
+    ModeGuard {
+        previous: MODE.with(|cell| cell.replace(mode)),
+    }
+}
+
+pub(crate) struct ModeGuard {
+    previous: DeserializationMode,
+}
+
+impl Drop for ModeGuard {
+    fn drop(&mut self) {
+        MODE.with(|cell| cell.set(self.previous));
+        MALFORMED.with(|malformed| malformed.borrow_mut().clear());
+    }
+}
+
+pub(crate) fn is_enabled() -> bool {
+    MODE.with(|cell| cell.get() == DeserializationMode::Lenient)
+}
+
+/// Falls back to the field's default when its value is malformed, setting the value aside so the
+/// model's `extra` fields keep it.
+pub(crate) fn recover<T, F>(field: &'static str, value: Value, deserialize: F) -> T
+where
+    T: Default,
+    F: FnOnce(Value) -> Result<T, serde_json::Error>,
+{
+    match deserialize(value.clone()) {
+        Ok(result) => result,
+        Err(error) => {
+            tracing::warn!(field, error = %error, "Ignoring a malformed value for an optional field");
+            MALFORMED.with(|malformed| malformed.borrow_mut().push((field, value)));
+            T::default()
+        }
+    }
+}
+
+/// Deserializes a model's unknown fields along with any malformed ones set aside while
+/// deserializing the rest of the model.
+pub(crate) fn extra<'de, D>(deserializer: D) -> Result<Map<String, Value>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let mut extra = Map::deserialize(deserializer)?;
+    MALFORMED.with(|malformed| {
+        for (field, value) in malformed.borrow_mut().drain(..) {
+            extra.insert(field.to_string(), value);
+        }
+    });
+
+    Ok(extra)
+}
+
+/// Defines a `deserialize_with` function for an optional field which, in lenient mode, replaces a
+/// malformed value with the field's default instead of failing the whole model.
+macro_rules! lenient_field {
+    ($name:ident, $field:literal, $type:ty) => {
+        lenient_field!(
+            $name,
+            $field,
+            $type,
+            <$type as serde::Deserialize>::deserialize
+        );
+    };
+    ($name:ident, $field:literal, $type:ty, $deserialize:expr) => {
+        fn $name<'de, D>(deserializer: D) -> Result<$type, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            if !crate::models::lenient::is_enabled() {
+                return $deserialize(deserializer);
+            }
+
+            let value = <serde_json::Value as serde::Deserialize>::deserialize(deserializer)?;
+            Ok(crate::models::lenient::recover($field, value, |value| {
+                $deserialize(value)
+            }))
+        }
+    };
+}
+
+pub(crate) use lenient_field;
@@ -1,4 +1,5 @@
-use crate::models::account::ReadingLogResponse;
+use crate::models::account::{ReadingLogResponse, Session};
+use chrono::{Duration, TimeZone, Utc};
 use std::error::Error;
 
 #[tokio::test]
@@ -13,3 +14,56 @@ pub async fn test_reading_log_response_serde() -> Result<(), Box<dyn Error>> {
     );
     Ok(())
 }
+
+#[test]
+fn test_session_parses_set_cookie_attributes() {
+    let session = Session::from(
+        "session=/people/reader%2Ctoken; Path=/; Expires=Wed, 21 Oct 2015 07:28:00 GMT; HttpOnly"
+            .to_string(),
+        "reader".to_string(),
+    );
+
+    assert_eq!(session.cookie(), "session=/people/reader%2Ctoken");
+    assert_eq!(session.name(), "session");
+    assert_eq!(session.value(), "/people/reader%2Ctoken");
+    assert_eq!(
+        session.expires_at(),
+        Some(Utc.with_ymd_and_hms(2015, 10, 21, 7, 28, 0).unwrap())
+    );
+    assert!(session.is_expired());
+}
+
+#[test]
+fn test_session_max_age_takes_precedence_over_expires() {
+    let session = Session::from(
+        "session=token; Expires=Wed, 21-Oct-2015 07:28:00 GMT; Max-Age=3600".to_string(),
+        "reader".to_string(),
+    );
+
+    assert!(!session.is_expired());
+    assert!(session.expires_at() > Some(Utc::now() + Duration::minutes(59)));
+}
+
+#[test]
+fn test_session_without_expiry_never_expires() {
+    let session = Session::from("session=token; Path=/".to_string(), "reader".to_string());
+
+    assert_eq!(session.expires_at(), None);
+    assert!(!session.is_expired());
+}
+
+#[test]
+fn test_session_serde() -> Result<(), Box<dyn Error>> {
+    let session = Session::from(
+        "session=token; Expires=Sun, 06 Nov 1994 08:49:37 GMT".to_string(),
+        "reader".to_string(),
+    );
+
+    let json = serde_json::to_string(&session)?;
+    assert_eq!(
+        json,
+        r#"{"cookie":"session=token","username":"reader","expires_at":"1994-11-06T08:49:37Z"}"#
+    );
+    assert_eq!(serde_json::from_str::<Session>(json.as_str())?, session);
+    Ok(())
+}
@@ -69,3 +69,18 @@ async fn test_serde_book_all_fields() -> Result<(), Box<dyn Error>> {
     // );
     Ok(())
 }
+
+#[test]
+fn test_book_keeps_unknown_fields() -> Result<(), Box<dyn Error>> {
+    let mut expected: serde_json::Value = serde_json::from_str(include_str!(
+        "../../clients/tests/books/resources/isbn.json"
+    ))?;
+    expected["ocaid"] = serde_json::json!("fundamentals00brin");
+
+    let book: Book = serde_json::from_value(expected.clone())?;
+    assert_eq!(book.extra.get("ocaid"), Some(&expected["ocaid"]));
+
+    let actual = serde_json::to_value(&book)?;
+    assert_eq!(actual["ocaid"], expected["ocaid"]);
+    Ok(())
+}
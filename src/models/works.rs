@@ -1,15 +1,15 @@
-use crate::format::KeyedValue;
-use crate::models::authors::{AuthorReference, AuthorType};
+use crate::models::authors::AuthorReference;
+use crate::models::lenient;
 use crate::models::{OpenLibraryModel, OpenLibraryResource};
-use chrono::NaiveDate;
-use serde::{Deserialize, Deserializer, Serialize};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
 /// Represents a logical collection of similar Editions.
 // The fields present per Work varies by instance so to better understand the distribution a key
 // frequency distribution was created with 10 million records. This client won't support anything
 // over 20% until a reason to do so presents itself. For a detailed view of field frequencies, view
 // `models` directory README.
-#[derive(Deserialize, Debug, Eq, PartialEq, Serialize)]
+#[derive(Clone, Deserialize, Debug, Eq, PartialEq, Serialize)]
 pub struct Work {
     pub title: String,
     #[serde(default)]
@@ -27,6 +27,9 @@ pub struct Work {
     pub revision: u32,
     // pub created: TODO need to support
     // pub last_modified TODO need to support
+    /// Fields this client doesn't model, kept so re-serializing a work doesn't lose them.
+    #[serde(flatten, deserialize_with = "lenient::extra")]
+    pub extra: Map<String, Value>,
 }
 
 impl OpenLibraryModel for Work {}
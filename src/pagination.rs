@@ -0,0 +1,159 @@
+use crate::OpenLibraryError;
+use futures::future::BoxFuture;
+use futures::stream::{self, BoxStream, Stream, StreamExt};
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use url::Url;
+
+/// Identifies where the next page of results should be requested from. Some endpoints return a
+/// `next` link to follow while others are paged by offset.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PageCursor {
+    Link(Url),
+    Offset(u32),
+}
+
+#[derive(Debug)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next: Option<PageCursor>,
+}
+
+type PageFetcher<T> = Arc<
+    dyn Fn(PageCursor, u32) -> BoxFuture<'static, Result<Page<T>, OpenLibraryError>> + Send + Sync,
+>;
+type StopPredicate<T> = Arc<dyn Fn(&T) -> bool + Send + Sync>;
+
+/// A lazily evaluated [`Stream`] over every item of a paged endpoint. No request is sent until the
+/// stream is first polled and subsequent pages are only requested once the current one is drained.
+pub struct Paginated<T> {
+    fetcher: PageFetcher<T>,
+    start: PageCursor,
+    page_size: u32,
+    max_items: Option<usize>,
+    stop_predicate: Option<StopPredicate<T>>,
+    stream: Option<BoxStream<'static, Result<T, OpenLibraryError>>>,
+}
+
+impl<T> Paginated<T>
+where
+    T: Send + 'static,
+{
+    pub(crate) fn new<F>(start: PageCursor, page_size: u32, fetcher: F) -> Self
+    where
+        F: Fn(PageCursor, u32) -> BoxFuture<'static, Result<Page<T>, OpenLibraryError>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        Self {
+            fetcher: Arc::new(fetcher),
+            start,
+            page_size,
+            max_items: None,
+            stop_predicate: None,
+            stream: None,
+        }
+    }
+
+    pub fn with_page_size(self, page_size: u32) -> Self {
+        Self { page_size, ..self }
+    }
+
+    pub fn with_max_items(self, max_items: usize) -> Self {
+        Self {
+            max_items: Some(max_items),
+            ..self
+        }
+    }
+
+    /// Ends the stream at the first item matching the predicate, without yielding that item.
+    pub fn with_stop_predicate<P>(self, predicate: P) -> Self
+    where
+        P: Fn(&T) -> bool + Send + Sync + 'static,
+    {
+        Self {
+            stop_predicate: Some(Arc::new(predicate)),
+            ..self
+        }
+    }
+
+    fn build_stream(&self) -> BoxStream<'static, Result<T, OpenLibraryError>> {
+        let state = PaginationState {
+            fetcher: self.fetcher.clone(),
+            cursor: Some(self.start.clone()),
+            buffer: VecDeque::new(),
+            page_size: self.page_size,
+            remaining: self.max_items,
+            stop_predicate: self.stop_predicate.clone(),
+        };
+
+        stream::unfold(state, |mut state| async move {
+            let item = state.next().await?;
+            Some((item, state))
+        })
+        .boxed()
+    }
+}
+
+impl<T> Stream for Paginated<T>
+where
+    T: Send + 'static,
+{
+    type Item = Result<T, OpenLibraryError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.stream.is_none() {
+            this.stream = Some(this.build_stream());
+        }
+
+        match this.stream.as_mut() {
+            Some(stream) => stream.as_mut().poll_next(cx),
+            None => Poll::Ready(None),
+        }
+    }
+}
+
+struct PaginationState<T> {
+    fetcher: PageFetcher<T>,
+    cursor: Option<PageCursor>,
+    buffer: VecDeque<T>,
+    page_size: u32,
+    remaining: Option<usize>,
+    stop_predicate: Option<StopPredicate<T>>,
+}
+
+impl<T> PaginationState<T> {
+    async fn next(&mut self) -> Option<Result<T, OpenLibraryError>> {
+        loop {
+            if self.remaining == Some(0) {
+                return None;
+            }
+
+            if let Some(item) = self.buffer.pop_front() {
+                if let Some(predicate) = &self.stop_predicate {
+                    if predicate(&item) {
+                        self.remaining = Some(0);
+                        return None;
+                    }
+                }
+                self.remaining = self.remaining.map(|remaining| remaining - 1);
+                return Some(Ok(item));
+            }
+
+            let cursor = self.cursor.take()?;
+            match (self.fetcher)(cursor, self.page_size).await {
+                Ok(page) => {
+                    if !page.items.is_empty() {
+                        self.cursor = page.next;
+                    }
+                    self.buffer.extend(page.items);
+                }
+                Err(error) => return Some(Err(error)),
+            }
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use crate::OpenLibraryError;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use url::Url;
+
+/// A token bucket allowing `requests` per `period`, with up to `burst` requests sent back to back.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RateLimit {
+    requests: u32,
+    period: Duration,
+    burst: u32,
+}
+
+impl RateLimit {
+    pub fn new(requests: u32, period: Duration) -> Self {
+        let requests = requests.max(1);
+        Self {
+            requests,
+            period,
+            burst: requests,
+        }
+    }
+
+    pub fn per_second(requests: u32) -> Self {
+        Self::new(requests, Duration::from_secs(1))
+    }
+
+    pub fn per_minute(requests: u32) -> Self {
+        Self::new(requests, Duration::from_secs(60))
+    }
+
+    pub fn with_burst(self, burst: u32) -> Self {
+        Self {
+            burst: burst.max(1),
+            ..self
+        }
+    }
+}
+
+/// Whether a request exceeding the rate limit waits for a token or fails immediately.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum RateLimitMode {
+    #[default]
+    Wait,
+    Fail,
+}
+
+/// Shared by every sub-client built from the same builder, so all requests draw from the same
+/// buckets. Requests to the covers host use a separate bucket from the rest of the API.
+#[derive(Clone, Default)]
+pub(crate) struct RateLimiter {
+    main: Option<Arc<TokenBucket>>,
+    covers: Option<Arc<TokenBucket>>,
+    mode: RateLimitMode,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(
+        main: Option<RateLimit>,
+        covers: Option<RateLimit>,
+        mode: RateLimitMode,
+    ) -> Self {
+        Self {
+            main: main.map(|limit| Arc::new(TokenBucket::new(limit))),
+            covers: covers.map(|limit| Arc::new(TokenBucket::new(limit))),
+            mode,
+        }
+    }
+
+    pub(crate) async fn acquire(&self, url: &Url) -> Result<(), OpenLibraryError> {
+        let is_covers = url
+            .host_str()
+            .map(|host| host.starts_with("covers."))
+            .unwrap_or(false);
+        let bucket = match is_covers {
+            true => &self.covers,
+            false => &self.main,
+        };
+
+        let bucket = match bucket {
+            Some(bucket) => bucket,
+            None => return Ok(()),
+        };
+
+        loop {
+            let wait = match bucket.try_acquire() {
+                Ok(()) => return Ok(()),
+                Err(wait) => wait,
+            };
+
+            match self.mode {
+                RateLimitMode::Wait => tokio::time::sleep(wait).await,
+                RateLimitMode::Fail => {
+                    return Err(OpenLibraryError::RateLimitExceeded {
+                        host: url.host_str().unwrap_or_default().to_string(),
+                        retry_after: wait,
+                    })
+                }
+            }
+        }
+    }
+}
+
+struct TokenBucket {
+    capacity: f64,
+    tokens_per_second: f64,
+    state: Mutex<BucketState>,
+}
+
+struct BucketState {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    fn new(limit: RateLimit) -> Self {
+        Self {
+            capacity: limit.burst as f64,
+            tokens_per_second: limit.requests as f64 / limit.period.as_secs_f64().max(f64::EPSILON),
+            state: Mutex::new(BucketState {
+                tokens: limit.burst as f64,
+                refilled_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// Takes a token if one is available, otherwise returns how long until one will be.
+    fn try_acquire(&self) -> Result<(), Duration> {
+        let mut state = self.state.lock().unwrap_or_else(|error| error.into_inner());
+        let now = Instant::now();
+        let elapsed = now.duration_since(state.refilled_at).as_secs_f64();
+        state.tokens = (state.tokens + elapsed * self.tokens_per_second).min(self.capacity);
+        state.refilled_at = now;
+
+        match state.tokens >= 1.0 {
+            true => {
+                state.tokens -= 1.0;
+                Ok(())
+            }
+            false => Err(Duration::from_secs_f64(
+                (1.0 - state.tokens) / self.tokens_per_second,
+            )),
+        }
+    }
+}
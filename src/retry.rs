@@ -0,0 +1,222 @@
+use crate::transport::HttpResponse;
+use crate::OpenLibraryError;
+use chrono::{DateTime, Utc};
+use http::header::RETRY_AFTER;
+use http::{HeaderMap, Method, StatusCode};
+use rand::Rng;
+use std::future::Future;
+use std::time::Duration;
+
+/// Controls how failed requests are retried. Only idempotent requests are retried unless
+/// [`RetryPolicy::with_non_idempotent_retries`] is enabled.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    jitter: bool,
+    respect_retry_after: bool,
+    retry_non_idempotent: bool,
+    retryable_statuses: Vec<StatusCode>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            jitter: true,
+            respect_retry_after: true,
+            retry_non_idempotent: false,
+            retryable_statuses: vec![
+                StatusCode::TOO_MANY_REQUESTS,
+                StatusCode::BAD_GATEWAY,
+                StatusCode::SERVICE_UNAVAILABLE,
+                StatusCode::GATEWAY_TIMEOUT,
+            ],
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy which sends every request exactly once.
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_max_attempts(self, max_attempts: u32) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            ..self
+        }
+    }
+
+    pub fn with_backoff(self, initial_backoff: Duration, max_backoff: Duration) -> Self {
+        Self {
+            initial_backoff,
+            max_backoff,
+            ..self
+        }
+    }
+
+    pub fn with_jitter(self, jitter: bool) -> Self {
+        Self { jitter, ..self }
+    }
+
+    pub fn with_retry_after(self, respect_retry_after: bool) -> Self {
+        Self {
+            respect_retry_after,
+            ..self
+        }
+    }
+
+    pub fn with_non_idempotent_retries(self, retry_non_idempotent: bool) -> Self {
+        Self {
+            retry_non_idempotent,
+            ..self
+        }
+    }
+
+    pub fn with_retryable_statuses(self, retryable_statuses: Vec<StatusCode>) -> Self {
+        Self {
+            retryable_statuses,
+            ..self
+        }
+    }
+
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    pub(crate) fn attempts_for(&self, method: &Method) -> u32 {
+        match self.retry_non_idempotent || is_idempotent(method) {
+            true => self.max_attempts,
+            false => 1,
+        }
+    }
+
+    pub(crate) fn is_retryable(&self, status: StatusCode) -> bool {
+        self.retryable_statuses.contains(&status)
+    }
+
+    /// Determines how long to wait before the next attempt, or `None` if the server asked for a
+    /// longer pause than this policy is willing to wait.
+    pub(crate) fn delay(&self, attempt: u32, headers: Option<&HeaderMap>) -> Option<Duration> {
+        if self.respect_retry_after {
+            if let Some(retry_after) = headers.and_then(retry_after) {
+                return match retry_after > self.max_backoff {
+                    true => None,
+                    false => Some(retry_after),
+                };
+            }
+        }
+
+        let exponent = attempt.saturating_sub(1).min(16);
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(2u32.pow(exponent))
+            .min(self.max_backoff);
+
+        Some(match self.jitter {
+            true => backoff / 2 + backoff.mul_f64(rand::thread_rng().gen_range(0.0..0.5)),
+            false => backoff,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RetryAttempt {
+    pub attempt: u32,
+    pub status_code: Option<StatusCode>,
+    pub reason: String,
+    pub delay: Duration,
+}
+
+fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::PUT | Method::DELETE | Method::OPTIONS | Method::TRACE
+    )
+}
+
+// The `Retry-After` header can either be a number of seconds or an HTTP date
+// https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Retry-After
+pub(crate) fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    match value.parse::<u64>() {
+        Ok(seconds) => Some(Duration::from_secs(seconds)),
+        Err(_) => {
+            let date = DateTime::parse_from_rfc2822(value).ok()?;
+            Some(
+                (date.with_timezone(&Utc) - Utc::now())
+                    .to_std()
+                    .unwrap_or(Duration::ZERO),
+            )
+        }
+    }
+}
+
+/// Sends a request through `send`, which is given the attempt number, until it succeeds, fails in
+/// a way retrying won't fix or the policy runs out of attempts.
+pub(crate) async fn send_with_retries<F, Fut>(
+    policy: &RetryPolicy,
+    method: &Method,
+    mut send: F,
+) -> Result<HttpResponse, OpenLibraryError>
+where
+    F: FnMut(u32) -> Fut,
+    Fut: Future<Output = Result<HttpResponse, OpenLibraryError>>,
+{
+    let max_attempts = policy.attempts_for(method);
+    let mut attempts: Vec<RetryAttempt> = Vec::new();
+    loop {
+        let attempt = attempts.len() as u32 + 1;
+        let result = send(attempt).await;
+        let (status_code, reason, delay) = match &result {
+            Ok(response) if policy.is_retryable(response.status) => (
+                Some(response.status),
+                format!("Received a {} response", response.status),
+                policy.delay(attempt, Some(&response.headers)),
+            ),
+            Err(error) if error.is_transient() => {
+                (None, error.to_string(), policy.delay(attempt, None))
+            }
+            _ => return result,
+        };
+
+        match delay {
+            Some(delay) if attempt < max_attempts => {
+                attempts.push(RetryAttempt {
+                    attempt,
+                    status_code,
+                    reason,
+                    delay,
+                });
+                tokio::time::sleep(delay).await;
+            }
+            _ if attempts.is_empty() => return result,
+            _ => {
+                attempts.push(RetryAttempt {
+                    attempt,
+                    status_code,
+                    reason,
+                    delay: Duration::ZERO,
+                });
+                let source = match result {
+                    Ok(response) => OpenLibraryError::from_response(&response),
+                    Err(error) => error,
+                };
+
+                return Err(OpenLibraryError::RetriesExhausted {
+                    attempts,
+                    source: Box::new(source),
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,381 @@
+//! [`tower`] integration, enabled with the `tower` feature.
+//!
+//! [`OpenLibraryClient::service`](crate::OpenLibraryClient::service) exposes the client's whole
+//! request pipeline as a [`Service`], while
+//! [`OpenLibraryClientBuilder::with_layer`](crate::OpenLibraryClientBuilder::with_layer) wraps
+//! each request the client sends over the network in layers of your own. [`RetryLayer`],
+//! [`RateLimitLayer`] and [`CacheLayer`] provide the client's own behaviour for stacks built
+//! without it.
+
+use crate::cache::{CacheConfig, CacheEntry, CacheLookup, ResponseCache};
+use crate::clients::HttpClient;
+use crate::rate_limit::{RateLimit, RateLimitMode, RateLimiter};
+use crate::retry::{self, RetryPolicy};
+use crate::transport::{HttpRequest, HttpResponse, HttpTransport};
+use crate::OpenLibraryError;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use http::header::{COOKIE, SET_COOKIE};
+use http::{Method, StatusCode};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::SystemTime;
+use tower::util::BoxCloneService;
+use tower::{BoxError, Layer, Service, ServiceExt};
+use url::Url;
+
+/// The type erased stack user supplied layers are applied to.
+pub type BoxHttpService = BoxCloneService<HttpRequest, HttpResponse, BoxError>;
+
+pub(crate) type LayerFn = Arc<dyn Fn(BoxHttpService) -> BoxHttpService + Send + Sync>;
+
+pub(crate) fn layer_fn<L>(layer: L) -> LayerFn
+where
+    L: Layer<BoxHttpService> + Send + Sync + 'static,
+    L::Service: Service<HttpRequest, Response = HttpResponse> + Clone + Send + 'static,
+    <L::Service as Service<HttpRequest>>::Error: Into<BoxError>,
+    <L::Service as Service<HttpRequest>>::Future: Send + 'static,
+{
+    Arc::new(move |inner| BoxCloneService::new(layer.layer(inner).map_err(Into::into)))
+}
+
+/// Recovers the client's error from one passed through a stack of layers.
+fn into_error(url: &Url, error: BoxError) -> OpenLibraryError {
+    match error.downcast::<OpenLibraryError>() {
+        Ok(error) => *error,
+        Err(error) if error.is::<tower::timeout::error::Elapsed>() => {
+            OpenLibraryError::Timeout { url: url.clone() }
+        }
+        Err(error) => OpenLibraryError::TransportError { source: error },
+    }
+}
+
+/// Sends requests over a transport, the innermost service of every stack.
+#[derive(Clone)]
+pub struct TransportService {
+    transport: Arc<dyn HttpTransport>,
+}
+
+impl TransportService {
+    pub fn new<T>(transport: T) -> Self
+    where
+        T: HttpTransport + 'static,
+    {
+        Self {
+            transport: Arc::new(transport),
+        }
+    }
+
+    pub(crate) fn from_arc(transport: Arc<dyn HttpTransport>) -> Self {
+        Self { transport }
+    }
+}
+
+impl Service<HttpRequest> for TransportService {
+    type Response = HttpResponse;
+    type Error = OpenLibraryError;
+    type Future = BoxFuture<'static, Result<HttpResponse, OpenLibraryError>>;
+
+    fn poll_ready(&mut self, _context: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: HttpRequest) -> Self::Future {
+        let transport = self.transport.clone();
+        async move { transport.send(request).await }.boxed()
+    }
+}
+
+/// Lets the client send its requests through a stack of layers.
+pub(crate) struct ServiceTransport {
+    // Boxed services aren't `Sync`, so each request works on its own clone
+    service: Mutex<BoxHttpService>,
+}
+
+impl ServiceTransport {
+    pub(crate) fn new(service: BoxHttpService) -> Self {
+        Self {
+            service: Mutex::new(service),
+        }
+    }
+}
+
+impl HttpTransport for ServiceTransport {
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, OpenLibraryError>> {
+        let service = self
+            .service
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+            .clone();
+        let url = request.url.clone();
+
+        async move {
+            service
+                .oneshot(request)
+                .await
+                .map_err(|error| into_error(&url, error))
+        }
+        .boxed()
+    }
+}
+
+/// The client's request pipeline, from applying the session and default headers through to
+/// retries, as a [`Service`].
+#[derive(Clone)]
+pub struct OpenLibraryService {
+    client: HttpClient,
+}
+
+impl OpenLibraryService {
+    pub(crate) fn new(client: HttpClient) -> Self {
+        Self { client }
+    }
+}
+
+impl Service<HttpRequest> for OpenLibraryService {
+    type Response = HttpResponse;
+    type Error = OpenLibraryError;
+    type Future = BoxFuture<'static, Result<HttpResponse, OpenLibraryError>>;
+
+    fn poll_ready(&mut self, _context: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: HttpRequest) -> Self::Future {
+        let client = self.client.clone();
+        async move { client.send(request).await }.boxed()
+    }
+}
+
+/// Retries requests according to a [`RetryPolicy`].
+#[derive(Clone, Debug)]
+pub struct RetryLayer {
+    policy: RetryPolicy,
+}
+
+impl RetryLayer {
+    pub fn new(policy: RetryPolicy) -> Self {
+        Self { policy }
+    }
+}
+
+impl<S> Layer<S> for RetryLayer {
+    type Service = RetryService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RetryService {
+            inner,
+            policy: self.policy.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RetryService<S> {
+    inner: S,
+    policy: RetryPolicy,
+}
+
+impl<S> Service<HttpRequest> for RetryService<S>
+where
+    S: Service<HttpRequest, Response = HttpResponse> + Clone + Send + 'static,
+    S::Error: Into<BoxError>,
+    S::Future: Send,
+{
+    type Response = HttpResponse;
+    type Error = OpenLibraryError;
+    type Future = BoxFuture<'static, Result<HttpResponse, OpenLibraryError>>;
+
+    fn poll_ready(&mut self, _context: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: HttpRequest) -> Self::Future {
+        let inner = self.inner.clone();
+        let policy = self.policy.clone();
+
+        async move {
+            let method = request.method.clone();
+            retry::send_with_retries(&policy, &method, move |_attempt| {
+                let (inner, request) = (inner.clone(), request.clone());
+                async move {
+                    let url = request.url.clone();
+                    inner
+                        .oneshot(request)
+                        .await
+                        .map_err(|error| into_error(&url, error.into()))
+                }
+            })
+            .await
+        }
+        .boxed()
+    }
+}
+
+/// Holds requests back, or fails them with `RateLimitExceeded`, to stay within a [`RateLimit`].
+/// Clones of the layer, and every service it builds, share the same budget.
+#[derive(Clone)]
+pub struct RateLimitLayer {
+    limiter: RateLimiter,
+}
+
+impl RateLimitLayer {
+    pub fn new(rate_limit: RateLimit, mode: RateLimitMode) -> Self {
+        Self {
+            limiter: RateLimiter::new(Some(rate_limit), None, mode),
+        }
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimitService {
+            inner,
+            limiter: self.limiter.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct RateLimitService<S> {
+    inner: S,
+    limiter: RateLimiter,
+}
+
+impl<S> Service<HttpRequest> for RateLimitService<S>
+where
+    S: Service<HttpRequest, Response = HttpResponse> + Clone + Send + 'static,
+    S::Error: Into<BoxError>,
+    S::Future: Send,
+{
+    type Response = HttpResponse;
+    type Error = OpenLibraryError;
+    type Future = BoxFuture<'static, Result<HttpResponse, OpenLibraryError>>;
+
+    fn poll_ready(&mut self, _context: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: HttpRequest) -> Self::Future {
+        let inner = self.inner.clone();
+        let limiter = self.limiter.clone();
+
+        async move {
+            limiter.acquire(&request.url).await?;
+            let url = request.url.clone();
+            inner
+                .oneshot(request)
+                .await
+                .map_err(|error| into_error(&url, error.into()))
+        }
+        .boxed()
+    }
+}
+
+/// Serves successful GET responses from a cache for the configured default time to live. Requests
+/// carrying a session, account pages and responses setting cookies are never cached.
+#[derive(Clone)]
+pub struct CacheLayer {
+    cache: ResponseCache,
+}
+
+impl CacheLayer {
+    pub fn new(config: CacheConfig) -> Self {
+        Self {
+            cache: ResponseCache::new(config, false),
+        }
+    }
+}
+
+impl<S> Layer<S> for CacheLayer {
+    type Service = CacheService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        CacheService {
+            inner,
+            cache: self.cache.clone(),
+        }
+    }
+}
+
+/// Whether the URL belongs to an account, i.e. logging in or its reading log, which the client's
+/// own cache never stores either.
+fn is_private(url: &Url) -> bool {
+    url.path_segments()
+        .and_then(|mut segments| segments.next())
+        .is_some_and(|segment| segment == "account" || segment == "people")
+}
+
+#[derive(Clone)]
+pub struct CacheService<S> {
+    inner: S,
+    cache: ResponseCache,
+}
+
+impl<S> Service<HttpRequest> for CacheService<S>
+where
+    S: Service<HttpRequest, Response = HttpResponse> + Clone + Send + 'static,
+    S::Error: Into<BoxError>,
+    S::Future: Send,
+{
+    type Response = HttpResponse;
+    type Error = OpenLibraryError;
+    type Future = BoxFuture<'static, Result<HttpResponse, OpenLibraryError>>;
+
+    fn poll_ready(&mut self, _context: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: HttpRequest) -> Self::Future {
+        let inner = self.inner.clone();
+        let cache = self.cache.clone();
+
+        async move {
+            let url = request.url.clone();
+            let key = match request.method == Method::GET
+                && !cache.default_ttl().is_zero()
+                && !request.headers.contains_key(COOKIE)
+                && !is_private(&url)
+            {
+                true => Some(url.to_string()),
+                false => None,
+            };
+
+            if let Some(key) = &key {
+                if let CacheLookup::Fresh(entry) = cache.lookup(key) {
+                    return Ok(HttpResponse {
+                        status: StatusCode::OK,
+                        headers: entry.headers,
+                        url: entry.url.unwrap_or(url),
+                        body: entry.body.into(),
+                    });
+                }
+            }
+
+            let response = inner
+                .oneshot(request)
+                .await
+                .map_err(|error| into_error(&url, error.into()))?;
+            let cacheable =
+                response.status == StatusCode::OK && !response.headers.contains_key(SET_COOKIE);
+            if let (Some(key), true) = (&key, cacheable) {
+                cache.put(
+                    key,
+                    CacheEntry {
+                        body: response.body.to_vec(),
+                        expires_at: SystemTime::now() + cache.default_ttl(),
+                        etag: None,
+                        last_modified: None,
+                        headers: response.headers.clone(),
+                        url: Some(response.url.clone()),
+                    },
+                );
+            }
+
+            Ok(response)
+        }
+        .boxed()
+    }
+}
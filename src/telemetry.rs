@@ -0,0 +1,117 @@
+use crate::{Endpoint, OpenLibraryError};
+use http::StatusCode;
+use std::future::Future;
+use std::time::Instant;
+use tracing::field::Empty;
+use tracing::{Instrument, Span};
+use url::Url;
+
+/// Runs a single API call within an `open_library.request` span, recording how long it took and,
+/// with the `metrics` feature, updating the request, error and latency metrics for the endpoint.
+pub(crate) async fn observe<T, F>(
+    endpoint: Endpoint,
+    url: &Url,
+    request: F,
+) -> Result<T, OpenLibraryError>
+where
+    F: Future<Output = Result<T, OpenLibraryError>>,
+{
+    let span = tracing::info_span!(
+        "open_library.request",
+        endpoint = endpoint_name(endpoint),
+        resource = url.path(),
+        status = Empty,
+        attempt = Empty,
+        cache_hit = Empty,
+        latency_ms = Empty,
+    );
+
+    let started = Instant::now();
+    let result = request.instrument(span.clone()).await;
+    let latency = started.elapsed();
+    span.record("latency_ms", latency.as_millis() as u64);
+
+    match &result {
+        Ok(_) => tracing::debug!(parent: &span, "Open Library request completed"),
+        Err(error) => tracing::warn!(parent: &span, error = %error, "Open Library request failed"),
+    }
+
+    #[cfg(feature = "metrics")]
+    {
+        let endpoint = endpoint_name(endpoint);
+        metrics::counter!("open_library_requests_total", "endpoint" => endpoint).increment(1);
+        metrics::histogram!("open_library_request_duration_seconds", "endpoint" => endpoint)
+            .record(latency.as_secs_f64());
+
+        if let Err(error) = &result {
+            metrics::counter!(
+                "open_library_errors_total",
+                "endpoint" => endpoint,
+                "error" => error_name(error)
+            )
+            .increment(1);
+        }
+    }
+
+    result
+}
+
+pub(crate) fn record_status(status: StatusCode) {
+    Span::current().record("status", status.as_u16());
+}
+
+pub(crate) fn record_attempt(attempt: u32) {
+    Span::current().record("attempt", attempt);
+}
+
+pub(crate) fn record_cache_hit(cache_hit: bool) {
+    Span::current().record("cache_hit", cache_hit);
+}
+
+fn endpoint_name(endpoint: Endpoint) -> &'static str {
+    match endpoint {
+        Endpoint::GetAuthor => "get_author",
+        Endpoint::GetAuthorWorks => "get_author_works",
+        Endpoint::SearchAuthors => "search_authors",
+        Endpoint::GetBook => "get_book",
+        Endpoint::GetBookByIsbn => "get_book_by_isbn",
+        Endpoint::SearchBooks => "search_books",
+        Endpoint::GetWork => "get_work",
+        Endpoint::GetReadingLog => "get_reading_log",
+        Endpoint::Login => "login",
+        Endpoint::Logout => "logout",
+        Endpoint::WhoAmI => "whoami",
+        Endpoint::UpdateReadingLog => "update_reading_log",
+        Endpoint::RateWork => "rate_work",
+        Endpoint::CreateList => "create_list",
+        Endpoint::UpdateList => "update_list",
+        Endpoint::DeleteList => "delete_list",
+    }
+}
+
+#[cfg(feature = "metrics")]
+fn error_name(error: &OpenLibraryError) -> &'static str {
+    match error {
+        OpenLibraryError::ApiError { .. } => "api_error",
+        OpenLibraryError::CassetteError { .. } => "cassette_error",
+        OpenLibraryError::ClientBuildingError { .. } => "client_building_error",
+        OpenLibraryError::CoalescedRequestFailed { source } => error_name(source),
+        OpenLibraryError::DeserializationError { .. } => "deserialization_error",
+        OpenLibraryError::InternalError { .. } => "internal_error",
+        OpenLibraryError::JsonParseError { .. } => "json_parse_error",
+        OpenLibraryError::LoginFailed { .. } => "login_failed",
+        OpenLibraryError::NotAuthenticated { .. } => "not_authenticated",
+        OpenLibraryError::NotFound { .. } => "not_found",
+        OpenLibraryError::ParsingError { .. } => "parsing_error",
+        OpenLibraryError::RateLimitExceeded { .. } => "rate_limit_exceeded",
+        OpenLibraryError::RateLimited { .. } => "rate_limited",
+        OpenLibraryError::Redirected { .. } => "redirected",
+        OpenLibraryError::RequestFailed { .. } => "request_failed",
+        OpenLibraryError::RetriesExhausted { .. } => "retries_exhausted",
+        OpenLibraryError::ServerError { .. } => "server_error",
+        OpenLibraryError::SessionStoreError { .. } => "session_store_error",
+        OpenLibraryError::Timeout { .. } => "timeout",
+        OpenLibraryError::TransportError { .. } => "transport_error",
+        OpenLibraryError::Unauthorized { .. } => "unauthorized",
+    }
+}
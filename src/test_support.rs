@@ -0,0 +1,494 @@
+//! An in-process fake of the Open Library API for exercising code built on this client without
+//! network access. Enabled with the `test-support` feature.
+
+use crate::models::account::{ReadingLog, ReadingLogEntry};
+use crate::models::authors::AuthorDetails;
+use crate::models::books::{Book, BookIdentifierKey};
+use crate::models::works::Work;
+use crate::models::OpenLibraryResource;
+use crate::{OpenLibraryClient, OpenLibraryClientBuilder};
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use url::Url;
+use wiremock::matchers::any;
+use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+const DEFAULT_READING_LOG_PAGE_SIZE: usize = 100;
+const DEFAULT_WORKS_PAGE_SIZE: usize = 50;
+const DEFAULT_SEARCH_PAGE_SIZE: usize = 100;
+
+/// Seed data for a [`FakeOpenLibraryServer`].
+#[derive(Default)]
+pub struct FakeOpenLibrary {
+    books: Vec<Book>,
+    works: Vec<Work>,
+    authors: Vec<AuthorDetails>,
+    users: HashMap<String, String>,
+    reading_logs: HashMap<(String, String), Vec<ReadingLogEntry>>,
+}
+
+impl FakeOpenLibrary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_book(mut self, book: Book) -> Self {
+        self.books.push(book);
+        self
+    }
+
+    pub fn with_work(mut self, work: Work) -> Self {
+        self.works.push(work);
+        self
+    }
+
+    pub fn with_author(mut self, author: AuthorDetails) -> Self {
+        self.authors.push(author);
+        self
+    }
+
+    /// Registers an account which can log in with the given password. Reading logs belonging to
+    /// a registered user are only served to requests carrying that user's session cookie.
+    pub fn with_user(mut self, username: &str, password: &str) -> Self {
+        self.users
+            .insert(username.to_string(), password.to_string());
+        self
+    }
+
+    pub fn with_reading_log(
+        mut self,
+        username: &str,
+        reading_log: ReadingLog,
+        entries: Vec<ReadingLogEntry>,
+    ) -> Self {
+        self.reading_logs
+            .entry((username.to_string(), reading_log.url().to_string()))
+            .or_default()
+            .extend(entries);
+        self
+    }
+
+    pub async fn start(self) -> FakeOpenLibraryServer {
+        let server = MockServer::start().await;
+        Mock::given(any())
+            .respond_with(FakeResponder {
+                data: Arc::new(self),
+                sessions: Arc::new(Mutex::new(HashMap::new())),
+            })
+            .mount(&server)
+            .await;
+
+        FakeOpenLibraryServer { server }
+    }
+}
+
+pub struct FakeOpenLibraryServer {
+    server: MockServer,
+}
+
+impl FakeOpenLibraryServer {
+    pub fn url(&self) -> Url {
+        Url::parse(self.server.uri().as_str()).expect("Mock server URI should be a valid URL")
+    }
+
+    /// A client builder already pointed at this server.
+    pub fn client_builder(&self) -> OpenLibraryClientBuilder {
+        OpenLibraryClient::builder().with_host(self.url())
+    }
+
+    /// The requests received so far, in the order they arrived.
+    pub async fn received_requests(&self) -> Vec<Request> {
+        self.server.received_requests().await.unwrap_or_default()
+    }
+}
+
+#[derive(Deserialize)]
+struct LoginForm {
+    username: String,
+    password: String,
+}
+
+struct FakeResponder {
+    data: Arc<FakeOpenLibrary>,
+    // Session tokens issued by the login endpoint, mapped to the user they belong to
+    sessions: Arc<Mutex<HashMap<String, String>>>,
+}
+
+impl Respond for FakeResponder {
+    fn respond(&self, request: &Request) -> ResponseTemplate {
+        let segments = request
+            .url
+            .path_segments()
+            .map(|segments| segments.filter(|s| !s.is_empty()).collect::<Vec<_>>())
+            .unwrap_or_default();
+        let query = request
+            .url
+            .query_pairs()
+            .into_owned()
+            .collect::<HashMap<String, String>>();
+
+        match (request.method.as_ref(), segments.as_slice()) {
+            ("GET", ["isbn", file]) => self.isbn(file),
+            ("GET", ["books", file]) => self.book(file),
+            ("GET", ["works", file]) => self.work(file),
+            ("GET", ["authors", file]) => self.author(file),
+            ("GET", ["authors", identifier, "works.json"]) => self.author_works(identifier, &query),
+            ("GET", ["api", "books"]) => self.search_books(&query),
+            ("GET", ["search", "authors.json"]) => self.search_authors(&query),
+            ("POST", ["account", "login"]) => self.login(&request.body),
+            ("POST", ["account", "logout"]) => self.logout(request),
+            ("GET", ["account"]) => self.account(request),
+            ("GET", ["account", "login"]) => ResponseTemplate::new(200),
+            ("GET", ["people", username]) => self.profile(username),
+            ("GET", ["people", username, "books", reading_log]) => {
+                self.reading_log(request, username, reading_log, &query)
+            }
+            _ => not_found(request.url.path()),
+        }
+    }
+}
+
+impl FakeResponder {
+    fn isbn(&self, file: &str) -> ResponseTemplate {
+        let isbn = match file.strip_suffix(".json") {
+            Some(isbn) => isbn,
+            None => return not_found(format!("/isbn/{}", file).as_str()),
+        };
+
+        match self.find_book_by(
+            isbn,
+            &[
+                BookIdentifierKey::InternationalStandard10,
+                BookIdentifierKey::InternationalStandard13,
+            ],
+        ) {
+            Some(book) => ResponseTemplate::new(200).set_body_json(book),
+            None => not_found(format!("/isbn/{}", isbn).as_str()),
+        }
+    }
+
+    fn book(&self, file: &str) -> ResponseTemplate {
+        let book = file
+            .strip_suffix(".json")
+            .and_then(|identifier| self.find_book(identifier));
+
+        match book {
+            Some(book) => ResponseTemplate::new(200).set_body_json(book),
+            None => not_found(format!("/books/{}", file).as_str()),
+        }
+    }
+
+    fn work(&self, file: &str) -> ResponseTemplate {
+        let work = file.strip_suffix(".json").and_then(|identifier| {
+            self.data
+                .works
+                .iter()
+                .find(|work| work.key == OpenLibraryResource::Work(identifier.to_string()))
+        });
+
+        match work {
+            Some(work) => ResponseTemplate::new(200).set_body_json(work),
+            None => not_found(format!("/works/{}", file).as_str()),
+        }
+    }
+
+    fn author(&self, file: &str) -> ResponseTemplate {
+        let author = file
+            .strip_suffix(".json")
+            .and_then(|identifier| self.find_author(identifier));
+
+        match author {
+            Some(author) => ResponseTemplate::new(200).set_body_json(author),
+            None => not_found(format!("/authors/{}", file).as_str()),
+        }
+    }
+
+    fn author_works(&self, identifier: &str, query: &HashMap<String, String>) -> ResponseTemplate {
+        if self.find_author(identifier).is_none() {
+            return not_found(format!("/authors/{}", identifier).as_str());
+        }
+
+        let works = self.works_by(identifier);
+        let limit = parameter(query, "limit").unwrap_or(DEFAULT_WORKS_PAGE_SIZE);
+        let offset = parameter(query, "offset").unwrap_or(0);
+        let page = works.iter().skip(offset).take(limit).collect::<Vec<_>>();
+
+        let mut links = json!({
+            "self": format!("/authors/{}/works.json?limit={}&offset={}", identifier, limit, offset),
+            "author": format!("/authors/{}", identifier),
+        });
+        if offset + page.len() < works.len() {
+            links["next"] = json!(format!(
+                "/authors/{}/works.json?limit={}&offset={}",
+                identifier,
+                limit,
+                offset + page.len()
+            ));
+        }
+
+        ResponseTemplate::new(200).set_body_json(json!({
+            "links": links,
+            "size": works.len(),
+            "entries": page,
+        }))
+    }
+
+    fn search_books(&self, query: &HashMap<String, String>) -> ResponseTemplate {
+        let keys = query.get("bibkeys").cloned().unwrap_or_default();
+        let mut results = HashMap::new();
+
+        for key in keys.split(',').filter(|key| !key.is_empty()) {
+            let book = match key.split_once(':') {
+                Some(("ISBN", value)) => self.find_book_by(
+                    value,
+                    &[
+                        BookIdentifierKey::InternationalStandard10,
+                        BookIdentifierKey::InternationalStandard13,
+                    ],
+                ),
+                Some(("LCCN", value)) => {
+                    self.find_book_by(value, &[BookIdentifierKey::LibraryOfCongress])
+                }
+                Some(("OCLC", value)) => {
+                    self.find_book_by(value, &[BookIdentifierKey::OhioCollegeLibraryCenter])
+                }
+                Some(("OLID", value)) => self.find_book(value),
+                _ => None,
+            };
+
+            if let Some(book) = book {
+                results.insert(key.to_string(), book);
+            }
+        }
+
+        ResponseTemplate::new(200).set_body_json(results)
+    }
+
+    fn search_authors(&self, query: &HashMap<String, String>) -> ResponseTemplate {
+        let term = query.get("q").cloned().unwrap_or_default().to_lowercase();
+        let limit = parameter(query, "limit").unwrap_or(DEFAULT_SEARCH_PAGE_SIZE);
+        let offset = parameter(query, "offset").unwrap_or(0);
+
+        let matches = self
+            .data
+            .authors
+            .iter()
+            .filter(|author| {
+                std::iter::once(&author.name)
+                    .chain(author.alternate_names.iter())
+                    .any(|name| name.to_lowercase().contains(term.as_str()))
+            })
+            .collect::<Vec<_>>();
+
+        let docs = matches
+            .iter()
+            .skip(offset)
+            .take(limit)
+            .map(|author| {
+                let identifier = author.key.value();
+                let works = self.works_by(identifier.as_str());
+
+                json!({
+                    "key": identifier,
+                    "type": "author",
+                    "name": author.name,
+                    "alternate_names": author.alternate_names,
+                    "birth_date": author.birth_date,
+                    "top_work": works.first().map(|work| work.title.clone()).unwrap_or_default(),
+                    "work_count": works.len(),
+                    "top_subjects": works
+                        .iter()
+                        .flat_map(|work| work.subjects.iter())
+                        .take(10)
+                        .collect::<Vec<_>>(),
+                    "_version_": 1,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        ResponseTemplate::new(200).set_body_json(json!({
+            "numFound": matches.len(),
+            "start": offset,
+            "numFoundExact": true,
+            "docs": docs,
+        }))
+    }
+
+    fn login(&self, body: &[u8]) -> ResponseTemplate {
+        let form = match serde_json::from_slice::<LoginForm>(body) {
+            Ok(form) => form,
+            Err(_) => return error(400, "Invalid login request"),
+        };
+
+        match self.data.users.get(&form.username) {
+            Some(password) if *password == form.password => {
+                let token = rand::thread_rng()
+                    .sample_iter(&Alphanumeric)
+                    .take(32)
+                    .map(char::from)
+                    .collect::<String>();
+                let cookie = format!("/people/{}%2C{}", form.username, token);
+                self.sessions
+                    .lock()
+                    .unwrap_or_else(|error| error.into_inner())
+                    .insert(cookie.clone(), form.username);
+
+                ResponseTemplate::new(200)
+                    .insert_header("Set-Cookie", format!("session={}; Path=/", cookie).as_str())
+                    .set_body_json(json!({}))
+            }
+            Some(_) => error(401, "account_incorrect_password"),
+            None => error(401, "account_user_notfound"),
+        }
+    }
+
+    fn logout(&self, request: &Request) -> ResponseTemplate {
+        if let Some(cookie) = session_cookie(request) {
+            self.sessions
+                .lock()
+                .unwrap_or_else(|error| error.into_inner())
+                .remove(&cookie);
+        }
+
+        ResponseTemplate::new(200).set_body_json(json!({}))
+    }
+
+    // Like Open Library, sends logged in users to their profile and everyone else to the login page
+    fn account(&self, request: &Request) -> ResponseTemplate {
+        let location = match self.session_user(request) {
+            Some(username) => format!("/people/{}", username),
+            None => "/account/login".to_string(),
+        };
+
+        ResponseTemplate::new(302).insert_header("Location", location.as_str())
+    }
+
+    fn profile(&self, username: &str) -> ResponseTemplate {
+        match self.data.users.contains_key(username) {
+            true => ResponseTemplate::new(200).set_body_json(json!({
+                "key": format!("/people/{}", username),
+                "type": { "key": "/type/user" },
+            })),
+            false => not_found(format!("/people/{}", username).as_str()),
+        }
+    }
+
+    fn reading_log(
+        &self,
+        request: &Request,
+        username: &str,
+        reading_log: &str,
+        query: &HashMap<String, String>,
+    ) -> ResponseTemplate {
+        if self.data.users.contains_key(username)
+            && self.session_user(request).as_deref() != Some(username)
+        {
+            // Open Library answers with an error document rather than an error status
+            return error(
+                200,
+                format!(
+                    "Shelf {} not found or not accessible",
+                    reading_log.trim_end_matches(".json")
+                )
+                .as_str(),
+            );
+        }
+
+        let entries = self
+            .data
+            .reading_logs
+            .get(&(username.to_string(), reading_log.to_string()))
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let page = parameter(query, "page").unwrap_or(1).max(1);
+        let limit = parameter(query, "limit").unwrap_or(DEFAULT_READING_LOG_PAGE_SIZE);
+
+        ResponseTemplate::new(200).set_body_json(json!({
+            "page": page,
+            "reading_log_entries": entries
+                .iter()
+                .skip((page - 1) * limit)
+                .take(limit)
+                .collect::<Vec<_>>(),
+        }))
+    }
+
+    fn session_user(&self, request: &Request) -> Option<String> {
+        let sessions = self
+            .sessions
+            .lock()
+            .unwrap_or_else(|error| error.into_inner());
+
+        session_cookie(request).and_then(|cookie| sessions.get(&cookie).cloned())
+    }
+
+    fn find_book(&self, identifier: &str) -> Option<&Book> {
+        self.data
+            .books
+            .iter()
+            .find(|book| book.key == OpenLibraryResource::Book(identifier.to_string()))
+    }
+
+    fn find_book_by(&self, value: &str, keys: &[BookIdentifierKey]) -> Option<&Book> {
+        self.data.books.iter().find(|book| {
+            keys.iter().any(|key| {
+                book.identifiers
+                    .get(key)
+                    .map(|values| values.iter().any(|candidate| candidate == value))
+                    .unwrap_or(false)
+            })
+        })
+    }
+
+    fn find_author(&self, identifier: &str) -> Option<&AuthorDetails> {
+        self.data
+            .authors
+            .iter()
+            .find(|author| author.key == OpenLibraryResource::Author(identifier.to_string()))
+    }
+
+    fn works_by(&self, identifier: &str) -> Vec<&Work> {
+        let author = OpenLibraryResource::Author(identifier.to_string());
+
+        self.data
+            .works
+            .iter()
+            .filter(|work| {
+                work.authors
+                    .iter()
+                    .any(|reference| reference.identifier.key == author)
+            })
+            .collect()
+    }
+}
+
+fn session_cookie(request: &Request) -> Option<String> {
+    request
+        .headers
+        .iter()
+        .filter(|(name, _)| name.as_str().eq_ignore_ascii_case("cookie"))
+        .flat_map(|(_, values)| values.iter())
+        .flat_map(|value| value.as_str().split(';'))
+        .find_map(|cookie| cookie.trim().strip_prefix("session="))
+        .map(str::to_string)
+}
+
+fn parameter(query: &HashMap<String, String>, name: &str) -> Option<usize> {
+    query.get(name).and_then(|value| value.parse().ok())
+}
+
+// Open Library answers unknown keys with a 404 naming the key it couldn't find
+fn not_found(key: &str) -> ResponseTemplate {
+    ResponseTemplate::new(404).set_body_json(json!({
+        "error": "notfound",
+        "key": key,
+    }))
+}
+
+fn error(status: u16, message: &str) -> ResponseTemplate {
+    ResponseTemplate::new(status).set_body_json(json!({ "error": message }))
+}
@@ -1,2 +1,42 @@
 #[cfg(test)]
+mod auth;
+#[cfg(test)]
+mod batch;
+#[cfg(all(test, feature = "blocking"))]
+mod blocking;
+#[cfg(test)]
+mod builder;
+#[cfg(test)]
+mod cache;
+#[cfg(test)]
+mod cassette;
+#[cfg(test)]
 pub mod clients;
+#[cfg(test)]
+mod coalescing;
+#[cfg(test)]
+mod deserialization;
+#[cfg(test)]
+mod errors;
+#[cfg(test)]
+mod failover;
+#[cfg(test)]
+mod fixtures;
+#[cfg(test)]
+mod interceptor;
+#[cfg(test)]
+mod lenient;
+#[cfg(test)]
+mod rate_limit;
+#[cfg(test)]
+mod raw;
+#[cfg(all(test, feature = "tower"))]
+mod service;
+#[cfg(test)]
+mod telemetry;
+#[cfg(all(test, feature = "test-support"))]
+mod test_support;
+#[cfg(test)]
+mod timeouts;
+#[cfg(test)]
+mod transport;
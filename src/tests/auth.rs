@@ -0,0 +1,448 @@
+use crate::auth::{Credentials, FileSessionStore, MemorySessionStore, SessionStore};
+use crate::models::account::{LoginRequest, Session};
+use crate::{OpenLibraryClient, OpenLibraryError, OpenLibraryErrorResponse};
+use futures::future::join_all;
+use http::header::{COOKIE, SET_COOKIE};
+use http::{Method, StatusCode};
+use std::error::Error;
+use std::time::Duration;
+use url::Url;
+use wiremock::matchers::{body_json, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const READING_LOG: &str = "/people/reader/books/want-to-read.json";
+
+fn expired_session() -> Session {
+    Session::from("session=expired".to_string(), "reader".to_string())
+}
+
+async fn mount_login(server: &MockServer, response: ResponseTemplate, expected_requests: u64) {
+    Mock::given(method(Method::POST.as_str()))
+        .and(path("/account/login"))
+        .and(body_json(LoginRequest {
+            username: "reader".to_string(),
+            password: "secret".to_string(),
+        }))
+        .respond_with(response)
+        .expect(expected_requests)
+        .mount(server)
+        .await;
+}
+
+async fn mount_reading_log(server: &MockServer, cookie: &str, response: ResponseTemplate) {
+    Mock::given(method(Method::GET.as_str()))
+        .and(path(READING_LOG))
+        .and(header(COOKIE.as_str(), cookie))
+        .respond_with(response)
+        .mount(server)
+        .await;
+}
+
+fn reading_log() -> ResponseTemplate {
+    ResponseTemplate::new(200)
+        .set_body_string(include_str!("../clients/tests/resources/want-to-read.json"))
+}
+
+fn session_error() -> OpenLibraryErrorResponse {
+    OpenLibraryErrorResponse {
+        error: "Your session has expired, please log in again".to_string(),
+    }
+}
+
+fn shelf_error() -> OpenLibraryErrorResponse {
+    OpenLibraryErrorResponse {
+        error: "Shelf want-to-read not found or not accessible".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_rejected_session_is_replaced_and_request_replayed() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .with_session(&expired_session())
+        .with_credentials_provider(Credentials::new("reader", "secret"))
+        .build()?;
+
+    mount_login(
+        &server,
+        ResponseTemplate::new(200).append_header(SET_COOKIE.as_str(), "session=fresh"),
+        1,
+    )
+    .await;
+    mount_reading_log(&server, "session=expired", ResponseTemplate::new(401)).await;
+    mount_reading_log(&server, "session=fresh", reading_log()).await;
+
+    let entries = client
+        .account
+        .get_want_to_read("reader".to_string())
+        .await?;
+
+    assert_eq!(entries.len(), 1);
+    assert_eq!(
+        client.session().map(|session| session.cookie().to_string()),
+        Some("session=fresh".to_string())
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_error_document_triggers_login() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .with_session(&expired_session())
+        .with_credentials_provider(Credentials::new("reader", "secret"))
+        .build()?;
+
+    mount_login(
+        &server,
+        ResponseTemplate::new(200).append_header(SET_COOKIE.as_str(), "session=fresh"),
+        1,
+    )
+    .await;
+    mount_reading_log(
+        &server,
+        "session=expired",
+        ResponseTemplate::new(200).set_body_json(session_error()),
+    )
+    .await;
+    mount_reading_log(&server, "session=fresh", reading_log()).await;
+
+    // Every sub-client, including those of derived clients, shares the replaced session
+    let entries = client
+        .with_timeout(Duration::from_secs(5))
+        .account
+        .get_want_to_read("reader".to_string())
+        .await?;
+
+    assert_eq!(entries.len(), 1);
+    assert_eq!(
+        client.session().map(|session| session.cookie().to_string()),
+        Some("session=fresh".to_string())
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_concurrent_rejections_log_in_once() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .with_session(&expired_session())
+        .with_credentials_provider(Credentials::new("reader", "secret"))
+        .build()?;
+
+    mount_login(
+        &server,
+        ResponseTemplate::new(200)
+            .append_header(SET_COOKIE.as_str(), "session=fresh")
+            .set_delay(Duration::from_millis(100)),
+        1,
+    )
+    .await;
+    mount_reading_log(&server, "session=expired", ResponseTemplate::new(401)).await;
+    mount_reading_log(&server, "session=fresh", reading_log()).await;
+
+    let results =
+        join_all((0..3).map(|_| client.account.get_want_to_read("reader".to_string()))).await;
+
+    for result in results {
+        assert_eq!(result?.len(), 1);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_request_is_replayed_only_once() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .with_session(&expired_session())
+        .with_credentials_provider(Credentials::new("reader", "secret"))
+        .build()?;
+
+    mount_login(
+        &server,
+        ResponseTemplate::new(200).append_header(SET_COOKIE.as_str(), "session=fresh"),
+        1,
+    )
+    .await;
+    Mock::given(method(Method::GET.as_str()))
+        .and(path(READING_LOG))
+        .respond_with(ResponseTemplate::new(401))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    match client.account.get_want_to_read("reader".to_string()).await {
+        Err(OpenLibraryError::Unauthorized { status_code, .. }) => {
+            assert_eq!(status_code, StatusCode::UNAUTHORIZED)
+        }
+        other => panic!("Expected an Unauthorized error but received {:?}!", other),
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_failed_login_is_returned() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .with_session(&expired_session())
+        .with_credentials_provider(Credentials::new("reader", "secret"))
+        .build()?;
+
+    mount_login(&server, ResponseTemplate::new(401), 1).await;
+    mount_reading_log(&server, "session=expired", ResponseTemplate::new(401)).await;
+
+    match client.account.get_want_to_read("reader".to_string()).await {
+        Err(OpenLibraryError::Unauthorized { url, .. }) => {
+            assert_eq!(url.path(), "/account/login")
+        }
+        other => panic!("Expected an Unauthorized error but received {:?}!", other),
+    }
+    assert_eq!(client.session(), Some(expired_session()));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sessions_are_not_replaced_without_credentials() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .with_session(&expired_session())
+        .build()?;
+
+    mount_login(&server, ResponseTemplate::new(200), 0).await;
+    mount_reading_log(&server, "session=expired", ResponseTemplate::new(403)).await;
+
+    assert!(matches!(
+        client.account.get_want_to_read("reader".to_string()).await,
+        Err(OpenLibraryError::Unauthorized { .. })
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_errors_about_other_accounts_keep_the_session() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .with_session(&expired_session())
+        .with_credentials_provider(Credentials::new("reader", "secret"))
+        .build()?;
+
+    mount_login(&server, ResponseTemplate::new(200), 0).await;
+    Mock::given(method(Method::GET.as_str()))
+        .and(path("/people/someone-else/books/want-to-read.json"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(shelf_error()))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    assert!(matches!(
+        client
+            .account
+            .get_want_to_read("someone-else".to_string())
+            .await,
+        Err(OpenLibraryError::ApiError { .. })
+    ));
+    assert_eq!(client.session(), Some(expired_session()));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_permanent_errors_on_own_shelves_keep_the_session() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .with_session(&expired_session())
+        .with_credentials_provider(Credentials::new("reader", "secret"))
+        .build()?;
+
+    mount_login(&server, ResponseTemplate::new(200), 0).await;
+    Mock::given(method(Method::GET.as_str()))
+        .and(path(READING_LOG))
+        .respond_with(ResponseTemplate::new(200).set_body_json(shelf_error()))
+        .expect(2)
+        .mount(&server)
+        .await;
+    Mock::given(method(Method::GET.as_str()))
+        .and(path("/people/reader/books/currently-reading.json"))
+        .respond_with(ResponseTemplate::new(403))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    // Neither the error document nor the forbidden response is retried with a new session
+    for _ in 0..2 {
+        assert!(matches!(
+            client.account.get_want_to_read("reader".to_string()).await,
+            Err(OpenLibraryError::ApiError { .. })
+        ));
+    }
+    assert!(matches!(
+        client
+            .account
+            .get_currently_reading("reader".to_string())
+            .await,
+        Err(OpenLibraryError::Unauthorized { .. })
+    ));
+    assert_eq!(client.session(), Some(expired_session()));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_logging_in_ignores_the_credentials_provider() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .with_credentials_provider(Credentials::new("provider", "configured"))
+        .build()?;
+
+    Mock::given(method(Method::POST.as_str()))
+        .and(path("/account/login"))
+        .and(body_json(LoginRequest {
+            username: "provider".to_string(),
+            password: "configured".to_string(),
+        }))
+        .respond_with(
+            ResponseTemplate::new(200).append_header(SET_COOKIE.as_str(), "session=provider"),
+        )
+        .expect(0)
+        .mount(&server)
+        .await;
+    mount_login(
+        &server,
+        ResponseTemplate::new(200).append_header(SET_COOKIE.as_str(), "session=fresh"),
+        1,
+    )
+    .await;
+
+    let session = client
+        .login("reader".to_string(), "secret".to_string())
+        .await?
+        .session();
+    assert_eq!(
+        session.map(|session| session.cookie().to_string()),
+        Some("session=fresh".to_string())
+    );
+
+    // Nor does a wrong password fall back to the configured credentials
+    Mock::given(method(Method::POST.as_str()))
+        .and(path("/account/login"))
+        .respond_with(
+            ResponseTemplate::new(401)
+                .set_body_json(serde_json::json!({ "error": "account_incorrect_password" })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    assert!(matches!(
+        client
+            .login("reader".to_string(), "wrong".to_string())
+            .await
+            .map(|_client| ()),
+        Err(OpenLibraryError::LoginFailed { .. })
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_file_session_store_round_trip() -> Result<(), Box<dyn Error>> {
+    let directory = tempfile::tempdir()?;
+    let store = FileSessionStore::new(directory.path().join("open-library").join("session.json"));
+    assert_eq!(store.load()?, None);
+
+    let session = Session::from(
+        "session=stored; Path=/; Max-Age=3600".to_string(),
+        "reader".to_string(),
+    );
+    store.save(&session)?;
+
+    let server = MockServer::start().await;
+    mount_reading_log(&server, "session=stored", reading_log()).await;
+
+    let loaded = store.load()?.ok_or("The session was not stored")?;
+    assert_eq!(loaded, session);
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .with_session(&loaded)
+        .build()?;
+    let entries = client
+        .account
+        .get_want_to_read("reader".to_string())
+        .await?;
+    assert_eq!(entries.len(), 1);
+
+    store.clear()?;
+    store.clear()?;
+    assert_eq!(store.load()?, None);
+    Ok(())
+}
+
+#[test]
+fn test_file_session_store_rejects_corrupt_file() -> Result<(), Box<dyn Error>> {
+    let directory = tempfile::tempdir()?;
+    let path = directory.path().join("session.json");
+    std::fs::write(&path, "not json")?;
+
+    let result = FileSessionStore::new(path).load();
+    assert!(matches!(
+        result,
+        Err(OpenLibraryError::SessionStoreError { .. })
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_file_session_store_survives_concurrent_saves() -> Result<(), Box<dyn Error>> {
+    let directory = tempfile::tempdir()?;
+    let path = directory.path().join("session.json");
+    let sessions = (0..16)
+        .map(|saver| {
+            Session::from(
+                format!("session={}", "a".repeat((saver + 1) * 4096)),
+                format!("reader-{}", saver),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let savers = sessions
+        .iter()
+        .cloned()
+        .map(|session| {
+            let store = FileSessionStore::new(path.clone());
+            std::thread::spawn(move || {
+                for _ in 0..16 {
+                    store.save(&session).unwrap();
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for saver in savers {
+        saver.join().map_err(|_| "A saver panicked")?;
+    }
+
+    let loaded = FileSessionStore::new(path)
+        .load()?
+        .ok_or("The session was not stored")?;
+    assert!(sessions.contains(&loaded));
+    // Every temporary file was renamed into place
+    assert_eq!(std::fs::read_dir(directory.path())?.count(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_memory_session_store() -> Result<(), Box<dyn Error>> {
+    let store = MemorySessionStore::new();
+    assert_eq!(store.load()?, None);
+
+    store.save(&expired_session())?;
+    assert_eq!(store.load()?, Some(expired_session()));
+
+    store.clear()?;
+    assert_eq!(store.load()?, None);
+    Ok(())
+}
@@ -0,0 +1,135 @@
+use crate::batch::BatchOptions;
+use crate::models::identifiers::OpenLibraryIdentifier;
+use crate::models::works::Work;
+use crate::tests::fixtures::{mount_work, work};
+use crate::{OpenLibraryClient, OpenLibraryError};
+use http::Method;
+use std::error::Error;
+use std::str::FromStr;
+use std::time::Duration;
+use url::Url;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn titled(identifier: &str) -> Work {
+    let mut work = work().unwrap();
+    work.title = identifier.to_string();
+    work
+}
+
+fn identifiers(values: &[&str]) -> Vec<OpenLibraryIdentifier> {
+    values
+        .iter()
+        .map(|value| OpenLibraryIdentifier::from_str(value).unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_get_many_preserves_order_and_deduplicates() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .build()?;
+
+    mount_work(
+        &server,
+        "OL1W",
+        &titled("OL1W"),
+        Duration::from_millis(200),
+        1,
+    )
+    .await;
+    mount_work(&server, "OL2W", &titled("OL2W"), Duration::ZERO, 1).await;
+    mount_work(&server, "OL3W", &titled("OL3W"), Duration::ZERO, 1).await;
+
+    let response = client
+        .works
+        .get_many(
+            identifiers(&["OL1W", "OL2W", "OL1W", "OL3W"]),
+            BatchOptions::default().with_concurrency(3),
+        )
+        .await;
+
+    let titles = response
+        .into_iter()
+        .map(|(identifier, result)| (identifier.to_string(), result.unwrap().title))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        titles,
+        vec![
+            ("OL1W".to_string(), "OL1W".to_string()),
+            ("OL2W".to_string(), "OL2W".to_string()),
+            ("OL3W".to_string(), "OL3W".to_string()),
+        ]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_many_returns_completion_order_when_unordered() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .build()?;
+
+    mount_work(
+        &server,
+        "OL1W",
+        &titled("OL1W"),
+        Duration::from_millis(200),
+        1,
+    )
+    .await;
+    mount_work(&server, "OL2W", &titled("OL2W"), Duration::ZERO, 1).await;
+
+    let response = client
+        .works
+        .get_many(
+            identifiers(&["OL1W", "OL2W"]),
+            BatchOptions::default().with_preserve_order(false),
+        )
+        .await;
+
+    let order = response
+        .iter()
+        .map(|(identifier, _)| identifier.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(order, vec!["OL2W", "OL1W"]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_many_reports_errors_per_identifier() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .build()?;
+
+    mount_work(&server, "OL1W", &titled("OL1W"), Duration::ZERO, 1).await;
+    Mock::given(method(Method::GET.as_str()))
+        .and(path("/works/OL2W.json"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+
+    let response = client
+        .works
+        .get_many(identifiers(&["OL1W", "OL2W"]), BatchOptions::default())
+        .await;
+
+    assert_eq!(response.len(), 2);
+    assert!(response
+        .get(&OpenLibraryIdentifier::from_str("OL1W")?)
+        .unwrap()
+        .is_ok());
+    match response.get(&OpenLibraryIdentifier::from_str("OL2W")?) {
+        Some(Err(OpenLibraryError::NotFound { resource, .. })) => {
+            assert_eq!(resource.path(), "/works/OL2W.json")
+        }
+        other => panic!(
+            "Expected a NotFound error but received {:?} instead!",
+            other
+        ),
+    }
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use crate::blocking::OpenLibraryClient;
+use crate::models::account::{ReadingLog, ReadingLogResponse};
+use crate::models::identifiers::OpenLibraryIdentifier;
+use crate::models::works::Work;
+use http::Method;
+use std::error::Error;
+use std::str::FromStr;
+use url::Url;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+// The mock server runs on its own thread, so a throwaway runtime is only needed to set it up
+fn start_server(mocks: Vec<Mock>) -> Result<MockServer, Box<dyn Error>> {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+
+    Ok(runtime.block_on(async {
+        let server = MockServer::start().await;
+        for mock in mocks {
+            mock.mount(&server).await;
+        }
+        server
+    }))
+}
+
+#[test]
+fn test_blocking_client_returns_work() -> Result<(), Box<dyn Error>> {
+    let expected: Work =
+        serde_json::from_str(include_str!("../clients/tests/resources/work.json"))?;
+    let server = start_server(vec![Mock::given(method(Method::GET.as_str()))
+        .and(path("/works/OL92304270.json"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&expected))])?;
+
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .build_blocking()?;
+    let actual = client
+        .works
+        .get(&OpenLibraryIdentifier::from_str("OL92304270")?)?;
+
+    assert_eq!(actual, expected);
+    Ok(())
+}
+
+#[test]
+fn test_blocking_paginated_reading_log_iterates_pages() -> Result<(), Box<dyn Error>> {
+    let page: ReadingLogResponse =
+        serde_json::from_str(include_str!("../clients/tests/resources/want-to-read.json"))?;
+    let reading_log_path = "/people/mock_user/books/want-to-read.json";
+    let server = start_server(vec![
+        Mock::given(method(Method::GET.as_str()))
+            .and(path(reading_log_path))
+            .and(query_param("page", "1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&page)),
+        Mock::given(method(Method::GET.as_str()))
+            .and(path(reading_log_path))
+            .and(query_param("page", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "page": 2,
+                "reading_log_entries": []
+            }))),
+    ])?;
+
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .build_blocking()?;
+    let entries = client
+        .account
+        .get_reading_log_paginated(ReadingLog::WantToRead, "mock_user".to_string())?
+        .with_page_size(1)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    assert_eq!(entries.len(), 1);
+    Ok(())
+}
@@ -0,0 +1,106 @@
+use crate::models::account::Session;
+use crate::models::identifiers::OpenLibraryIdentifier;
+use crate::models::works::Work;
+use crate::OpenLibraryClient;
+use http::header::{COOKIE, USER_AGENT};
+use http::{HeaderMap, HeaderValue, Method};
+use std::error::Error;
+use std::str::FromStr;
+use url::Url;
+use wiremock::matchers::{header, headers, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn test_builder_sends_user_agent_and_merged_headers() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let session = Session::from("session=abc123".to_string(), "mock_user".to_string());
+
+    let mut headers = HeaderMap::new();
+    headers.insert("X-Gateway-Token", HeaderValue::from_static("token"));
+    headers.insert(COOKIE, HeaderValue::from_static("gateway=1"));
+
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .with_application("BookShelf", "2.1.0")
+        .with_contact("admin@example.com")
+        .with_default_headers(headers)
+        .with_session(&session)
+        .build()?;
+
+    let work: Work = serde_json::from_str(include_str!("../clients/tests/resources/work.json"))?;
+    Mock::given(method(Method::GET.as_str()))
+        .and(path("/works/OL92304270.json"))
+        .and(header(
+            USER_AGENT.as_str(),
+            format!(
+                "BookShelf/2.1.0 (admin@example.com) open-library/{}",
+                env!("CARGO_PKG_VERSION")
+            )
+            .as_str(),
+        ))
+        .and(header("X-Gateway-Token", "token"))
+        .and(header(COOKIE.as_str(), "gateway=1; session=abc123"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&work))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client
+        .works
+        .get(&OpenLibraryIdentifier::from_str("OL92304270")?)
+        .await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_builder_sends_every_value_of_default_headers() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+
+    let mut default_headers = HeaderMap::new();
+    default_headers.append("X-Feature", HeaderValue::from_static("a"));
+    default_headers.append("X-Feature", HeaderValue::from_static("b"));
+
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .with_default_headers(default_headers)
+        .build()?;
+
+    let work: Work = serde_json::from_str(include_str!("../clients/tests/resources/work.json"))?;
+    Mock::given(method(Method::GET.as_str()))
+        .and(path("/works/OL92304270.json"))
+        .and(headers("X-Feature", vec!["a", "b"]))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&work))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client
+        .works
+        .get(&OpenLibraryIdentifier::from_str("OL92304270")?)
+        .await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_login_sends_user_agent() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .build()?;
+
+    Mock::given(method(Method::POST.as_str()))
+        .and(path("/account/login"))
+        .and(header(
+            USER_AGENT.as_str(),
+            format!("open-library/{}", env!("CARGO_PKG_VERSION")).as_str(),
+        ))
+        .respond_with(ResponseTemplate::new(200).append_header("Set-Cookie", "session=abc123"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client
+        .login("mock_user".to_string(), "mock_password".to_string())
+        .await?;
+    Ok(())
+}
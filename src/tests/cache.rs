@@ -0,0 +1,250 @@
+use crate::cache::{
+    CacheConfig, CacheEntry, CacheMode, CacheStore, DiskCacheStore, MemoryCacheStore,
+};
+use crate::models::books::Book;
+use crate::models::identifiers::InternationalStandardBookNumber;
+use crate::{Endpoint, OpenLibraryClient};
+use http::header::{HeaderValue, CONTENT_TYPE};
+use http::{HeaderMap, Method};
+use std::error::Error;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+use url::Url;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn mount_isbn(server: &MockServer, expected_calls: u64) -> Result<Book, Box<dyn Error>> {
+    let book: Book =
+        serde_json::from_str(include_str!("../clients/tests/books/resources/isbn.json"))?;
+
+    Mock::given(method(Method::GET.as_str()))
+        .and(path("/isbn/0201558025.json"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&book))
+        .expect(expected_calls)
+        .mount(server)
+        .await;
+
+    Ok(book)
+}
+
+#[tokio::test]
+async fn test_cache_serves_repeated_lookups() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .with_cache(CacheConfig::new(MemoryCacheStore::new(10)))
+        .build()?;
+    let expected = mount_isbn(&server, 1).await?;
+
+    let isbn = InternationalStandardBookNumber::from_str("0201558025")?;
+    let first = client.books.by_isbn(isbn.clone()).await?;
+    let second = client.books.by_isbn(isbn).await?;
+
+    assert_eq!(first, expected);
+    assert_eq!(second, expected);
+
+    let stats = client.cache_stats().expect("Expected cache statistics!");
+    assert_eq!(stats.hits, 1);
+    assert_eq!(stats.misses, 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cache_mode_refresh_and_bypass_go_to_network() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .with_cache(CacheConfig::new(MemoryCacheStore::new(10)))
+        .build()?;
+    mount_isbn(&server, 3).await?;
+
+    let isbn = InternationalStandardBookNumber::from_str("0201558025")?;
+    client.books.by_isbn(isbn.clone()).await?;
+    client
+        .with_cache_mode(CacheMode::Refresh)
+        .books
+        .by_isbn(isbn.clone())
+        .await?;
+    client
+        .with_cache_mode(CacheMode::Bypass)
+        .books
+        .by_isbn(isbn.clone())
+        .await?;
+    client.books.by_isbn(isbn).await?;
+
+    let stats = client.cache_stats().expect("Expected cache statistics!");
+    assert_eq!(stats.hits, 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cache_skips_endpoints_with_zero_ttl() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .with_cache(
+            CacheConfig::new(MemoryCacheStore::new(10))
+                .with_ttl(Endpoint::GetBookByIsbn, Duration::ZERO),
+        )
+        .build()?;
+    mount_isbn(&server, 2).await?;
+
+    let isbn = InternationalStandardBookNumber::from_str("0201558025")?;
+    client.books.by_isbn(isbn.clone()).await?;
+    client.books.by_isbn(isbn).await?;
+    Ok(())
+}
+
+#[test]
+fn test_disk_cache_store_persists_entries() -> Result<(), Box<dyn Error>> {
+    let directory = tempfile::tempdir()?;
+    let entry = CacheEntry {
+        body: b"{\"title\": \"Hatchet\"}".to_vec(),
+        expires_at: SystemTime::now() + Duration::from_secs(60),
+        etag: Some("\"abc\"".to_string()),
+        last_modified: None,
+        headers: vec![(CONTENT_TYPE, HeaderValue::from_static("application/json"))]
+            .into_iter()
+            .collect(),
+        url: Some(Url::parse("https://openlibrary.org/books/OL1M.json")?),
+    };
+
+    DiskCacheStore::new(directory.path()).put("GET https://openlibrary.org/isbn/1", entry.clone());
+
+    let store = DiskCacheStore::new(directory.path());
+    let actual = store
+        .get("GET https://openlibrary.org/isbn/1")
+        .expect("Expected entry to be read back from disk!");
+
+    assert_eq!(actual.body, entry.body);
+    assert_eq!(actual.headers, entry.headers);
+    assert_eq!(actual.url, entry.url);
+    assert!(actual.is_fresh());
+    assert_eq!(store.get("GET https://openlibrary.org/isbn/2"), None);
+    Ok(())
+}
+
+#[test]
+fn test_disk_cache_store_survives_concurrent_writers() -> Result<(), Box<dyn Error>> {
+    let directory = tempfile::tempdir()?;
+    let store = std::sync::Arc::new(DiskCacheStore::new(directory.path()));
+    let bodies = (0..16_u8)
+        .map(|writer| vec![b'a' + writer; (usize::from(writer) + 1) * 64 * 1024])
+        .collect::<Vec<_>>();
+
+    let writers = bodies
+        .iter()
+        .cloned()
+        .map(|body| {
+            let store = store.clone();
+            std::thread::spawn(move || {
+                for _ in 0..16 {
+                    store.put(
+                        "GET https://openlibrary.org/isbn/1",
+                        CacheEntry {
+                            body: body.clone(),
+                            expires_at: SystemTime::now() + Duration::from_secs(60),
+                            etag: None,
+                            last_modified: None,
+                            headers: HeaderMap::new(),
+                            url: None,
+                        },
+                    );
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    // Every entry read back, even while writers are busy, needs to be one of theirs in full
+    let reader = {
+        let (store, bodies) = (store.clone(), bodies.clone());
+        std::thread::spawn(move || {
+            let mut torn = 0;
+            for _ in 0..1000 {
+                if let Some(entry) = store.get("GET https://openlibrary.org/isbn/1") {
+                    torn += usize::from(!bodies.contains(&entry.body));
+                }
+            }
+            torn
+        })
+    };
+    for writer in writers {
+        writer.join().map_err(|_| "A writer panicked")?;
+    }
+    assert_eq!(reader.join().map_err(|_| "The reader panicked")?, 0);
+
+    let actual = store
+        .get("GET https://openlibrary.org/isbn/1")
+        .expect("Expected entry to be read back from disk!");
+    assert!(bodies.contains(&actual.body));
+    assert_eq!(std::fs::read_dir(directory.path())?.count(), 1);
+    Ok(())
+}
+
+async fn mount_conditional_isbn(server: &MockServer) -> Result<Book, Box<dyn Error>> {
+    let book: Book =
+        serde_json::from_str(include_str!("../clients/tests/books/resources/isbn.json"))?;
+
+    Mock::given(method(Method::GET.as_str()))
+        .and(path("/isbn/0201558025.json"))
+        .and(header("If-None-Match", "\"v1\""))
+        .respond_with(ResponseTemplate::new(304))
+        .expect(1)
+        .mount(server)
+        .await;
+
+    Mock::given(method(Method::GET.as_str()))
+        .and(path("/isbn/0201558025.json"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .append_header("ETag", "\"v1\"")
+                .set_body_json(&book),
+        )
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(server)
+        .await;
+
+    Ok(book)
+}
+
+#[tokio::test]
+async fn test_conditional_requests_serve_not_modified_responses() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .with_conditional_requests(true)
+        .build()?;
+    let expected = mount_conditional_isbn(&server).await?;
+
+    let isbn = InternationalStandardBookNumber::from_str("0201558025")?;
+    let first = client.books.by_isbn(isbn.clone()).await?;
+    let second = client.books.by_isbn(isbn).await?;
+
+    assert_eq!(first, expected);
+    assert_eq!(second, expected);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_conditional_requests_revalidate_expired_cache_entries() -> Result<(), Box<dyn Error>>
+{
+    let server = MockServer::start().await;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .with_cache(
+            CacheConfig::new(MemoryCacheStore::new(10)).with_default_ttl(Duration::from_millis(1)),
+        )
+        .with_conditional_requests(true)
+        .build()?;
+    let expected = mount_conditional_isbn(&server).await?;
+
+    let isbn = InternationalStandardBookNumber::from_str("0201558025")?;
+    client.books.by_isbn(isbn.clone()).await?;
+    tokio::time::sleep(Duration::from_millis(5)).await;
+    let actual = client.books.by_isbn(isbn).await?;
+
+    assert_eq!(actual, expected);
+    let stats = client.cache_stats().expect("Expected cache statistics!");
+    assert_eq!(stats.revalidations, 1);
+    Ok(())
+}
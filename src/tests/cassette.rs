@@ -0,0 +1,173 @@
+use crate::cassette::{Cassette, MatchOn};
+use crate::models::identifiers::OpenLibraryIdentifier;
+use crate::models::works::Work;
+use crate::{OpenLibraryClient, OpenLibraryError};
+use http::header::SET_COOKIE;
+use http::Method;
+use std::error::Error;
+use std::str::FromStr;
+use tempfile::TempDir;
+use url::Url;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn test_cassette_replays_recorded_responses() -> Result<(), Box<dyn Error>> {
+    let directory = TempDir::new()?;
+    let cassette = directory.path().join("works.json");
+    let server = MockServer::start().await;
+    let host = Url::parse(server.uri().as_str())?;
+
+    let work: Work = serde_json::from_str(include_str!("../clients/tests/resources/work.json"))?;
+    Mock::given(method(Method::GET.as_str()))
+        .and(path("/works/OL92304270.json"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&work))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let recording = OpenLibraryClient::builder()
+        .with_host(host.clone())
+        .with_cassette(Cassette::record(&cassette))
+        .build()?;
+    recording
+        .works
+        .get(&OpenLibraryIdentifier::from_str("OL92304270")?)
+        .await?;
+    drop(server);
+
+    let replaying = OpenLibraryClient::builder()
+        .with_host(host)
+        .with_cassette(Cassette::replay(&cassette))
+        .build()?;
+    let actual = replaying
+        .works
+        .get(&OpenLibraryIdentifier::from_str("OL92304270")?)
+        .await?;
+
+    assert_eq!(actual, work);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_strict_cassette_fails_unmatched_requests() -> Result<(), Box<dyn Error>> {
+    let directory = TempDir::new()?;
+    let cassette = directory.path().join("search.json");
+    let server = MockServer::start().await;
+    let host = Url::parse(server.uri().as_str())?;
+
+    Mock::given(method(Method::GET.as_str()))
+        .and(path("/search/authors.json"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "numFound": 0,
+            "start": 0,
+            "numFoundExact": true,
+            "docs": []
+        })))
+        .mount(&server)
+        .await;
+
+    OpenLibraryClient::builder()
+        .with_host(host.clone())
+        .with_cassette(Cassette::record(&cassette))
+        .build()?
+        .author
+        .search("Tolkien")
+        .await?;
+
+    let client = OpenLibraryClient::builder()
+        .with_host(host.clone())
+        .with_cassette(Cassette::replay(&cassette))
+        .build()?;
+    match client.author.search("Pratchett").await {
+        Err(OpenLibraryError::CassetteError { .. }) => {}
+        other => panic!("Expected a CassetteError but received {:?} instead!", other),
+    }
+
+    let client = OpenLibraryClient::builder()
+        .with_host(host)
+        .with_cassette(
+            Cassette::replay(&cassette).with_match_on(vec![MatchOn::Method, MatchOn::Path]),
+        )
+        .build()?;
+    let response = client.author.search("Pratchett").await?;
+    assert_eq!(response.num_found, 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_replaying_missing_cassette_fails_to_build() {
+    let result = OpenLibraryClient::builder()
+        .with_cassette(Cassette::replay("does/not/exist.json"))
+        .build();
+
+    match result {
+        Err(OpenLibraryError::CassetteError { .. }) => {}
+        Err(error) => panic!("Expected a CassetteError but received {:?} instead!", error),
+        Ok(_) => panic!("Expected a CassetteError but the client was built instead!"),
+    }
+}
+
+#[tokio::test]
+async fn test_replaying_malformed_cassette_fails_to_build() -> Result<(), Box<dyn Error>> {
+    let directory = TempDir::new()?;
+    let path = directory.path().join("malformed.json");
+    std::fs::write(&path, "{\"interactions\": [")?;
+
+    match OpenLibraryClient::builder()
+        .with_cassette(Cassette::replay(&path))
+        .build()
+    {
+        Err(OpenLibraryError::CassetteError { reason }) => {
+            assert!(reason.contains(path.display().to_string().as_str()))
+        }
+        Err(error) => panic!("Expected a CassetteError but received {:?} instead!", error),
+        Ok(_) => panic!("Expected a CassetteError but the client was built instead!"),
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cassette_redacts_credentials_and_session_cookies() -> Result<(), Box<dyn Error>> {
+    let directory = TempDir::new()?;
+    let cassette = directory.path().join("login.json");
+    let server = MockServer::start().await;
+    let host = Url::parse(server.uri().as_str())?;
+
+    Mock::given(method(Method::POST.as_str()))
+        .and(path("/account/login"))
+        .respond_with(ResponseTemplate::new(200).append_header(
+            SET_COOKIE.as_str(),
+            "session=/people/reader%2Cs3cr3t-token; Path=/; HttpOnly",
+        ))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let recording = OpenLibraryClient::builder()
+        .with_host(host.clone())
+        .with_cassette(Cassette::record(&cassette))
+        .build()?;
+    recording
+        .login("reader".to_string(), "hunter2".to_string())
+        .await?;
+    drop(server);
+
+    let contents = std::fs::read_to_string(&cassette)?;
+    assert!(!contents.contains("hunter2"));
+    assert!(!contents.contains("s3cr3t-token"));
+
+    // The redacted cookie still logs the replaying client in
+    let replaying = OpenLibraryClient::builder()
+        .with_host(host)
+        .with_cassette(Cassette::replay(&cassette))
+        .build()?;
+    let session = replaying
+        .login("reader".to_string(), "hunter2".to_string())
+        .await?
+        .session()
+        .ok_or("Expected the replayed login to set a session")?;
+
+    assert_eq!(session.cookie(), "session=REDACTED");
+    Ok(())
+}
@@ -1,15 +1,18 @@
-use crate::clients::handle;
+use crate::clients::{handle, HttpClient};
 use crate::models::OpenLibraryModel;
-use crate::OpenLibraryError;
+use crate::retry::RetryPolicy;
+use crate::transport::ReqwestTransport;
+use crate::{Endpoint, OpenLibraryError};
 use http::{Method, StatusCode};
-use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::error::Error;
+use std::sync::Arc;
+use std::time::Duration;
 use url::Url;
 use wiremock::matchers::{method, path};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
-#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 struct FakeResponse {
     message: String,
 }
@@ -20,6 +23,7 @@ impl OpenLibraryModel for FakeResponse {}
 pub async fn test_get_returns_successfully() -> Result<(), Box<dyn Error>> {
     let server = MockServer::start().await;
     let base_url = Url::parse(server.uri().as_str())?;
+    let client = HttpClient::new(Arc::new(ReqwestTransport::default()));
     let url_path = "/some/fake/path";
     let expected = FakeResponse {
         message: "Some API response message".to_string(),
@@ -31,7 +35,12 @@ pub async fn test_get_returns_successfully() -> Result<(), Box<dyn Error>> {
         .mount(&server)
         .await;
 
-    let actual: FakeResponse = handle(Client::new().get(base_url.join(url_path)?)).await?;
+    let actual: FakeResponse = handle(
+        &client,
+        Endpoint::GetWork,
+        client.get(base_url.join(url_path)?),
+    )
+    .await?;
 
     assert_eq!(actual, expected);
     Ok(())
@@ -41,20 +50,26 @@ pub async fn test_get_returns_successfully() -> Result<(), Box<dyn Error>> {
 pub async fn test_get_returns_error_when_receives_invalid_json() -> Result<(), Box<dyn Error>> {
     let server = MockServer::start().await;
     let base_url = Url::parse(server.uri().as_str())?;
+    let client = HttpClient::new(Arc::new(ReqwestTransport::default()));
     let url_path = "/some/fake/path";
 
     Mock::given(method(Method::GET.as_str()))
         .and(path(url_path))
-        .respond_with(ResponseTemplate::new(200).set_body_json(&"{"))
+        .respond_with(ResponseTemplate::new(200).set_body_json("{"))
         .mount(&server)
         .await;
 
-    let response = handle::<FakeResponse>(Client::new().get(base_url.join(url_path)?)).await;
+    let response = handle::<FakeResponse>(
+        &client,
+        Endpoint::GetWork,
+        client.get(base_url.join(url_path)?),
+    )
+    .await;
     let actual =
         response.expect_err("Expected call to return error but it completed successfully!");
 
     match &actual {
-        OpenLibraryError::JsonParseError { source: _ } => Ok(()),
+        OpenLibraryError::DeserializationError { .. } => Ok(()),
         _ => panic!("Expected to received an error regarding json parsing but didn't!"),
     }
 }
@@ -63,6 +78,7 @@ pub async fn test_get_returns_error_when_receives_invalid_json() -> Result<(), B
 pub async fn test_get_returns_error_when_api_responds_with_error() -> Result<(), Box<dyn Error>> {
     let server = MockServer::start().await;
     let base_url = Url::parse(server.uri().as_str())?;
+    let client = HttpClient::new(Arc::new(ReqwestTransport::default()));
     let url_path = "/some/fake/path";
 
     Mock::given(method(Method::GET.as_str()))
@@ -71,15 +87,142 @@ pub async fn test_get_returns_error_when_api_responds_with_error() -> Result<(),
         .mount(&server)
         .await;
 
-    let response = handle::<FakeResponse>(Client::new().get(base_url.join(url_path)?)).await;
+    let response = handle::<FakeResponse>(
+        &client,
+        Endpoint::GetWork,
+        client.get(base_url.join(url_path)?),
+    )
+    .await;
     let actual =
         response.expect_err("Expected call to return error but it completed successfully!");
 
     match &actual {
-        OpenLibraryError::ApiError {
-            status_code: StatusCode::NOT_FOUND,
-            error: _,
-        } => Ok(()),
+        OpenLibraryError::NotFound { resource, .. } => {
+            assert_eq!(resource.path(), url_path);
+            Ok(())
+        }
         _ => panic!("Expected to received an error regarding json parsing but didn't!"),
     }
 }
+
+#[tokio::test]
+pub async fn test_get_retries_until_successful() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let base_url = Url::parse(server.uri().as_str())?;
+    let client = HttpClient::new(Arc::new(ReqwestTransport::default())).with_retry_policy(
+        RetryPolicy::default().with_backoff(Duration::from_millis(1), Duration::from_millis(5)),
+    );
+    let url_path = "/some/fake/path";
+    let expected = FakeResponse {
+        message: "Some API response message".to_string(),
+    };
+
+    Mock::given(method(Method::GET.as_str()))
+        .and(path(url_path))
+        .respond_with(ResponseTemplate::new(503).append_header("Retry-After", "0"))
+        .up_to_n_times(2)
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    Mock::given(method(Method::GET.as_str()))
+        .and(path(url_path))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&expected))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let actual: FakeResponse = handle(
+        &client,
+        Endpoint::GetWork,
+        client.get(base_url.join(url_path)?),
+    )
+    .await?;
+
+    assert_eq!(actual, expected);
+    Ok(())
+}
+
+#[tokio::test]
+pub async fn test_get_returns_attempt_history_when_retries_exhausted() -> Result<(), Box<dyn Error>>
+{
+    let server = MockServer::start().await;
+    let base_url = Url::parse(server.uri().as_str())?;
+    let client = HttpClient::new(Arc::new(ReqwestTransport::default())).with_retry_policy(
+        RetryPolicy::default()
+            .with_max_attempts(3)
+            .with_backoff(Duration::from_millis(1), Duration::from_millis(5)),
+    );
+    let url_path = "/some/fake/path";
+
+    Mock::given(method(Method::GET.as_str()))
+        .and(path(url_path))
+        .respond_with(ResponseTemplate::new(429))
+        .expect(3)
+        .mount(&server)
+        .await;
+
+    let response = handle::<FakeResponse>(
+        &client,
+        Endpoint::GetWork,
+        client.get(base_url.join(url_path)?),
+    )
+    .await;
+    let actual =
+        response.expect_err("Expected call to return error but it completed successfully!");
+
+    match &actual {
+        OpenLibraryError::RetriesExhausted { attempts, source } => {
+            assert_eq!(attempts.len(), 3);
+            assert!(attempts
+                .iter()
+                .all(|x| x.status_code == Some(StatusCode::TOO_MANY_REQUESTS)));
+            assert!(matches!(
+                source.as_ref(),
+                OpenLibraryError::RateLimited { .. }
+            ));
+            Ok(())
+        }
+        _ => panic!(
+            "Expected to receive a retries exhausted error but received {:?} instead!",
+            actual
+        ),
+    }
+}
+
+#[tokio::test]
+pub async fn test_post_is_not_retried_by_default() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let base_url = Url::parse(server.uri().as_str())?;
+    let client = HttpClient::new(Arc::new(ReqwestTransport::default())).with_retry_policy(
+        RetryPolicy::default().with_backoff(Duration::from_millis(1), Duration::from_millis(5)),
+    );
+    let url_path = "/some/fake/path";
+
+    Mock::given(method(Method::POST.as_str()))
+        .and(path(url_path))
+        .respond_with(ResponseTemplate::new(503))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let response = handle::<FakeResponse>(
+        &client,
+        Endpoint::GetWork,
+        client.post(base_url.join(url_path)?),
+    )
+    .await;
+    let actual =
+        response.expect_err("Expected call to return error but it completed successfully!");
+
+    match &actual {
+        OpenLibraryError::ServerError {
+            status_code: StatusCode::SERVICE_UNAVAILABLE,
+            ..
+        } => Ok(()),
+        _ => panic!(
+            "Expected to receive a server error but received {:?} instead!",
+            actual
+        ),
+    }
+}
@@ -0,0 +1,194 @@
+use crate::models::authors::AuthorDetails;
+use crate::models::identifiers::OpenLibraryIdentifier;
+use crate::{OpenLibraryClient, OpenLibraryError};
+use futures::future::join_all;
+use http::header::{COOKIE, SET_COOKIE};
+use http::{Method, StatusCode};
+use std::error::Error;
+use std::str::FromStr;
+use std::time::Duration;
+use url::Url;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const READING_LOG: &str = "/people/reader/books/want-to-read.json";
+
+async fn mount_author(server: &MockServer, response: ResponseTemplate, expected_requests: u64) {
+    Mock::given(method(Method::GET.as_str()))
+        .and(path("/authors/OL23919A.json"))
+        .respond_with(response.set_delay(Duration::from_millis(200)))
+        .expect(expected_requests)
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn test_concurrent_identical_requests_share_one_request() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .with_request_coalescing(true)
+        .build()?;
+
+    let expected: AuthorDetails = serde_json::from_str(include_str!(
+        "../clients/tests/author/resources/author.json"
+    ))?;
+    mount_author(
+        &server,
+        ResponseTemplate::new(200).set_body_json(&expected),
+        1,
+    )
+    .await;
+
+    let clients = (0..5).map(|_| client.clone()).collect::<Vec<_>>();
+    let results = join_all(clients.iter().map(|client| {
+        client
+            .author
+            .get(OpenLibraryIdentifier::from_str("OL23919A").unwrap())
+    }))
+    .await;
+
+    for result in results {
+        assert_eq!(result?, expected);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_requests_for_different_types_are_not_shared() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .with_request_coalescing(true)
+        .build()?;
+
+    let expected: AuthorDetails = serde_json::from_str(include_str!(
+        "../clients/tests/author/resources/author.json"
+    ))?;
+    mount_author(
+        &server,
+        ResponseTemplate::new(200).set_body_json(&expected),
+        2,
+    )
+    .await;
+
+    let identifier = OpenLibraryIdentifier::from_str("OL23919A")?;
+    let (model, raw) = futures::join!(
+        client.author.get(identifier.clone()),
+        client.author.get_raw(identifier)
+    );
+
+    assert_eq!(model?, expected);
+    assert_eq!(serde_json::from_value::<AuthorDetails>(raw?)?, expected);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_coalesced_failures_reach_every_caller() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .with_request_coalescing(true)
+        .build()?;
+
+    mount_author(&server, ResponseTemplate::new(503), 1).await;
+
+    let results = join_all((0..3).map(|_| {
+        client
+            .author
+            .get(OpenLibraryIdentifier::from_str("OL23919A").unwrap())
+    }))
+    .await;
+
+    // The error is shared rather than rebuilt for each caller
+    for result in results {
+        match result {
+            Err(OpenLibraryError::CoalescedRequestFailed { source }) => match source.as_ref() {
+                OpenLibraryError::ServerError { status_code, .. } => {
+                    assert_eq!(*status_code, StatusCode::SERVICE_UNAVAILABLE)
+                }
+                other => panic!("Expected a ServerError but received {:?} instead!", other),
+            },
+            other => panic!(
+                "Expected a CoalescedRequestFailed error but received {:?} instead!",
+                other
+            ),
+        }
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_requests_are_not_coalesced_by_default() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .build()?;
+
+    let expected: AuthorDetails = serde_json::from_str(include_str!(
+        "../clients/tests/author/resources/author.json"
+    ))?;
+    mount_author(
+        &server,
+        ResponseTemplate::new(200).set_body_json(&expected),
+        3,
+    )
+    .await;
+
+    join_all((0..3).map(|_| {
+        client
+            .author
+            .get(OpenLibraryIdentifier::from_str("OL23919A").unwrap())
+    }))
+    .await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_requests_with_different_sessions_are_not_shared() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let anonymous = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .with_request_coalescing(true)
+        .build()?;
+
+    Mock::given(method(Method::POST.as_str()))
+        .and(path("/account/login"))
+        .respond_with(
+            ResponseTemplate::new(200).append_header(SET_COOKIE.as_str(), "session=reader"),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method(Method::GET.as_str()))
+        .and(path(READING_LOG))
+        .and(header(COOKIE.as_str(), "session=reader"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string(include_str!("../clients/tests/resources/want-to-read.json"))
+                .set_delay(Duration::from_millis(200)),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method(Method::GET.as_str()))
+        .and(path(READING_LOG))
+        .respond_with(ResponseTemplate::new(403).set_delay(Duration::from_millis(200)))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let authenticated = anonymous
+        .login("reader".to_string(), "secret".to_string())
+        .await?;
+    let (anonymous, authenticated) = futures::join!(
+        anonymous.account.get_want_to_read("reader".to_string()),
+        authenticated.account.get_want_to_read("reader".to_string())
+    );
+
+    assert!(matches!(
+        anonymous,
+        Err(OpenLibraryError::Unauthorized { .. })
+    ));
+    assert_eq!(authenticated?.len(), 1);
+    Ok(())
+}
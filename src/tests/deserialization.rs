@@ -0,0 +1,100 @@
+use crate::models::identifiers::OpenLibraryIdentifier;
+use crate::models::works::Work;
+use crate::{OpenLibraryClient, OpenLibraryClientBuilder, OpenLibraryError};
+use http::Method;
+use serde_json::Value;
+use std::error::Error;
+use std::str::FromStr;
+use url::Url;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn get_work(
+    builder: OpenLibraryClientBuilder,
+    body: &str,
+) -> Result<OpenLibraryError, Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = builder
+        .with_host(Url::parse(server.uri().as_str())?)
+        .build()?;
+
+    Mock::given(method(Method::GET.as_str()))
+        .and(path("/works/OL92304270.json"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(body))
+        .mount(&server)
+        .await;
+
+    let result: Result<Work, OpenLibraryError> = client
+        .works
+        .get(&OpenLibraryIdentifier::from_str("OL92304270")?)
+        .await;
+    Ok(result.expect_err("Expected the call to fail but it completed successfully!"))
+}
+
+fn work_with(pointer: &str, value: Value) -> Result<String, Box<dyn Error>> {
+    let mut work: Value =
+        serde_json::from_str(include_str!("../clients/tests/resources/work.json"))?;
+    *work
+        .pointer_mut(pointer)
+        .ok_or("The pointer does not exist in the work fixture")? = value;
+    Ok(work.to_string())
+}
+
+#[tokio::test]
+async fn test_error_reports_path_of_failing_field() -> Result<(), Box<dyn Error>> {
+    let body = work_with("/authors/0/author/key", Value::from(42))?;
+    let error = get_work(OpenLibraryClient::builder(), body.as_str()).await?;
+
+    match &error {
+        OpenLibraryError::DeserializationError { url, source, body } => {
+            assert_eq!(url.path(), "/works/OL92304270.json");
+            assert_eq!(source.path().to_string(), "authors[0].author.key");
+            assert!(source
+                .inner()
+                .to_string()
+                .contains("invalid type: integer `42`"));
+            assert!(body.is_none());
+        }
+        other => panic!("Expected a DeserializationError but received {:?}!", other),
+    }
+    assert!(error.to_string().contains("authors[0].author.key"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_error_captures_capped_body_when_enabled() -> Result<(), Box<dyn Error>> {
+    let body = work_with("/revision", Value::from("two"))?;
+    let error = get_work(
+        OpenLibraryClient::builder().with_error_body_capture(16),
+        body.as_str(),
+    )
+    .await?;
+
+    match &error {
+        OpenLibraryError::DeserializationError {
+            source,
+            body: captured,
+            ..
+        } => {
+            assert_eq!(source.path().to_string(), "revision");
+            assert_eq!(captured.as_deref(), Some(&body[..16]));
+        }
+        other => panic!("Expected a DeserializationError but received {:?}!", other),
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_trailing_characters_are_rejected() -> Result<(), Box<dyn Error>> {
+    let body = format!(
+        "{} trailing",
+        include_str!("../clients/tests/resources/work.json")
+    );
+    let error = get_work(OpenLibraryClient::builder(), body.as_str()).await?;
+
+    assert!(matches!(
+        error,
+        OpenLibraryError::DeserializationError { .. }
+    ));
+    Ok(())
+}
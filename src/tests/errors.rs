@@ -0,0 +1,179 @@
+use crate::models::identifiers::OpenLibraryIdentifier;
+use crate::models::works::Work;
+use crate::{OpenLibraryClient, OpenLibraryError, OpenLibraryErrorResponse};
+use http::{Method, StatusCode};
+use std::error::Error;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use url::Url;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn get_work(response: ResponseTemplate) -> Result<OpenLibraryError, Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .build()?;
+
+    Mock::given(method(Method::GET.as_str()))
+        .and(path("/works/OL92304270.json"))
+        .respond_with(response)
+        .mount(&server)
+        .await;
+
+    let result: Result<Work, OpenLibraryError> = client
+        .works
+        .get(&OpenLibraryIdentifier::from_str("OL92304270")?)
+        .await;
+    Ok(result.expect_err("Expected the call to fail but it completed successfully!"))
+}
+
+fn error_body(message: &str) -> OpenLibraryErrorResponse {
+    OpenLibraryErrorResponse {
+        error: message.to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_not_found_keeps_resource_and_error_body() -> Result<(), Box<dyn Error>> {
+    let error = get_work(ResponseTemplate::new(404).set_body_json(error_body("notfound"))).await?;
+
+    match &error {
+        OpenLibraryError::NotFound { resource, error } => {
+            assert_eq!(resource.path(), "/works/OL92304270.json");
+            assert_eq!(error.as_ref(), Some(&error_body("notfound")));
+        }
+        other => panic!(
+            "Expected a NotFound error but received {:?} instead!",
+            other
+        ),
+    }
+    assert!(!error.is_retryable());
+    assert!(!error.is_transient());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_too_many_requests_is_rate_limited() -> Result<(), Box<dyn Error>> {
+    let error = get_work(ResponseTemplate::new(429).insert_header("Retry-After", "7")).await?;
+
+    match &error {
+        OpenLibraryError::RateLimited { url, retry_after } => {
+            assert_eq!(url.path(), "/works/OL92304270.json");
+            assert_eq!(*retry_after, Some(Duration::from_secs(7)));
+        }
+        other => panic!(
+            "Expected a RateLimited error but received {:?} instead!",
+            other
+        ),
+    }
+    assert!(error.is_retryable());
+    assert!(!error.is_transient());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_unauthorized_statuses() -> Result<(), Box<dyn Error>> {
+    for status in [StatusCode::UNAUTHORIZED, StatusCode::FORBIDDEN] {
+        let error = get_work(
+            ResponseTemplate::new(status.as_u16()).set_body_json(error_body("Shelf is private")),
+        )
+        .await?;
+
+        match &error {
+            OpenLibraryError::Unauthorized {
+                status_code, error, ..
+            } => {
+                assert_eq!(*status_code, status);
+                assert_eq!(error.as_ref(), Some(&error_body("Shelf is private")));
+            }
+            other => panic!("Expected an Unauthorized error but received {:?}!", other),
+        }
+        assert!(!error.is_retryable());
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_server_errors_are_retryable_when_temporary() -> Result<(), Box<dyn Error>> {
+    let error = get_work(ResponseTemplate::new(503)).await?;
+    assert!(matches!(
+        error,
+        OpenLibraryError::ServerError {
+            status_code: StatusCode::SERVICE_UNAVAILABLE,
+            error: None,
+            ..
+        }
+    ));
+    assert!(error.is_retryable());
+
+    let error = get_work(ResponseTemplate::new(501)).await?;
+    assert!(matches!(error, OpenLibraryError::ServerError { .. }));
+    assert!(!error.is_retryable());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_unfollowed_redirect_is_reported() -> Result<(), Box<dyn Error>> {
+    let error =
+        get_work(ResponseTemplate::new(300).insert_header("Location", "/works/OL1W.json")).await?;
+
+    match &error {
+        OpenLibraryError::Redirected {
+            url,
+            status_code,
+            location,
+        } => {
+            assert_eq!(url.path(), "/works/OL92304270.json");
+            assert_eq!(*status_code, StatusCode::MULTIPLE_CHOICES);
+            assert_eq!(location.as_deref(), Some("/works/OL1W.json"));
+        }
+        other => panic!(
+            "Expected a Redirected error but received {:?} instead!",
+            other
+        ),
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_other_statuses_remain_api_errors() -> Result<(), Box<dyn Error>> {
+    let error = get_work(ResponseTemplate::new(400).set_body_string("not json")).await?;
+
+    match &error {
+        OpenLibraryError::ApiError {
+            url,
+            status_code,
+            error,
+        } => {
+            assert_eq!(url.path(), "/works/OL92304270.json");
+            assert_eq!(*status_code, StatusCode::BAD_REQUEST);
+            assert!(error.is_none());
+        }
+        other => panic!("Expected an ApiError but received {:?} instead!", other),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_transient_errors_are_retryable() -> Result<(), Box<dyn Error>> {
+    let timeout = OpenLibraryError::Timeout {
+        url: Url::parse("https://openlibrary.org/works/OL1W.json")?,
+    };
+    assert!(timeout.is_transient());
+    assert!(timeout.is_retryable());
+
+    let shared = OpenLibraryError::CoalescedRequestFailed {
+        source: Arc::new(timeout),
+    };
+    assert!(shared.is_transient());
+    assert!(shared.is_retryable());
+
+    let parsing = OpenLibraryError::ParsingError {
+        reason: "invalid".to_string(),
+    };
+    assert!(!parsing.is_transient());
+    assert!(!parsing.is_retryable());
+    Ok(())
+}
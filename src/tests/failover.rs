@@ -0,0 +1,224 @@
+use crate::failover::FailoverPolicy;
+use crate::models::account::Session;
+use crate::models::identifiers::OpenLibraryIdentifier;
+use crate::tests::fixtures::{work, WORK};
+use crate::{OpenLibraryClient, OpenLibraryError};
+use futures::future::join_all;
+use http::header::COOKIE;
+use http::Method;
+use std::error::Error;
+use std::str::FromStr;
+use std::time::Duration;
+use url::Url;
+use wiremock::matchers::{header_exists, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn client(
+    mirror: &MockServer,
+    primary: &MockServer,
+    policy: FailoverPolicy,
+) -> Result<OpenLibraryClient, Box<dyn Error>> {
+    Ok(OpenLibraryClient::builder()
+        .with_host(Url::parse(primary.uri().as_str())?)
+        .with_hosts(vec![
+            Url::parse(mirror.uri().as_str())?,
+            Url::parse(primary.uri().as_str())?,
+        ])
+        .with_failover_policy(policy)
+        .build()?)
+}
+
+#[tokio::test]
+async fn test_reads_prefer_the_first_host() -> Result<(), Box<dyn Error>> {
+    let (mirror, primary) = (MockServer::start().await, MockServer::start().await);
+    let client = client(&mirror, &primary, FailoverPolicy::default())?;
+
+    Mock::given(method(Method::GET.as_str()))
+        .and(path(WORK))
+        .respond_with(ResponseTemplate::new(200).set_body_json(work()?))
+        .expect(1)
+        .mount(&mirror)
+        .await;
+    Mock::given(method(Method::GET.as_str()))
+        .and(path(WORK))
+        .respond_with(ResponseTemplate::new(200).set_body_json(work()?))
+        .expect(0)
+        .mount(&primary)
+        .await;
+
+    client
+        .works
+        .get(&OpenLibraryIdentifier::from_str("OL92304270")?)
+        .await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_mirror_path_prefixes_are_kept() -> Result<(), Box<dyn Error>> {
+    let (mirror, primary) = (MockServer::start().await, MockServer::start().await);
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(primary.uri().as_str())?)
+        .with_hosts(vec![
+            Url::parse(format!("{}/openlibrary", mirror.uri()).as_str())?,
+            Url::parse(primary.uri().as_str())?,
+        ])
+        .build()?;
+
+    Mock::given(method(Method::GET.as_str()))
+        .and(path(format!("/openlibrary{}", WORK)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(work()?))
+        .expect(1)
+        .mount(&mirror)
+        .await;
+    Mock::given(method(Method::GET.as_str()))
+        .and(path(WORK))
+        .respond_with(ResponseTemplate::new(200).set_body_json(work()?))
+        .expect(0)
+        .mount(&primary)
+        .await;
+
+    let actual = client
+        .works
+        .get(&OpenLibraryIdentifier::from_str("OL92304270")?)
+        .await?;
+    assert_eq!(actual, work()?);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_mirror_miss_falls_back_without_ejecting() -> Result<(), Box<dyn Error>> {
+    let (mirror, primary) = (MockServer::start().await, MockServer::start().await);
+    let client = client(
+        &mirror,
+        &primary,
+        FailoverPolicy::default().with_failure_threshold(1),
+    )?;
+
+    Mock::given(method(Method::GET.as_str()))
+        .and(path(WORK))
+        .respond_with(ResponseTemplate::new(404))
+        .expect(2)
+        .mount(&mirror)
+        .await;
+    Mock::given(method(Method::GET.as_str()))
+        .and(path(WORK))
+        .respond_with(ResponseTemplate::new(200).set_body_json(work()?))
+        .expect(2)
+        .mount(&primary)
+        .await;
+
+    for _ in 0..2 {
+        let actual = client
+            .works
+            .get(&OpenLibraryIdentifier::from_str("OL92304270")?)
+            .await?;
+        assert_eq!(actual, work()?);
+    }
+
+    let health = client.host_health().ok_or("Expected host health")?;
+    assert!(health.iter().all(|host| host.healthy));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_failing_host_is_ejected_until_cooldown() -> Result<(), Box<dyn Error>> {
+    let (mirror, primary) = (MockServer::start().await, MockServer::start().await);
+    let client = client(
+        &mirror,
+        &primary,
+        FailoverPolicy::default()
+            .with_failure_threshold(2)
+            .with_cooldown(Duration::from_millis(200)),
+    )?;
+
+    Mock::given(method(Method::GET.as_str()))
+        .and(path(WORK))
+        .respond_with(ResponseTemplate::new(503).set_delay(Duration::from_millis(100)))
+        .expect(3)
+        .mount(&mirror)
+        .await;
+    Mock::given(method(Method::GET.as_str()))
+        .and(path(WORK))
+        .respond_with(ResponseTemplate::new(200).set_body_json(work()?))
+        .expect(6)
+        .mount(&primary)
+        .await;
+
+    let identifier = OpenLibraryIdentifier::from_str("OL92304270")?;
+    for _ in 0..3 {
+        client.works.get(&identifier).await?;
+    }
+
+    let health = client.host_health().ok_or("Expected host health")?;
+    let mirror_health = health
+        .iter()
+        .find(|host| host.host.as_str().starts_with(mirror.uri().as_str()))
+        .ok_or("Expected the mirror's health")?;
+    assert!(!mirror_health.healthy);
+    assert_eq!(mirror_health.consecutive_failures, 2);
+
+    // Once the cooldown passes the mirror receives a single trial request while the others skip
+    // it, and failing the trial ejects it again
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    let results = join_all((0..3).map(|_| client.works.get(&identifier))).await;
+    for result in results {
+        result?;
+    }
+
+    let health = client.host_health().ok_or("Expected host health")?;
+    assert!(health
+        .iter()
+        .any(|host| host.host.as_str().starts_with(mirror.uri().as_str()) && !host.healthy));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_writes_and_sessions_stay_on_the_primary() -> Result<(), Box<dyn Error>> {
+    let (mirror, primary) = (MockServer::start().await, MockServer::start().await);
+    let session = Session::from("session=primary".to_string(), "reader".to_string());
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(primary.uri().as_str())?)
+        .with_hosts(vec![Url::parse(mirror.uri().as_str())?])
+        .with_session(&session)
+        .build()?;
+
+    Mock::given(method(Method::POST.as_str()))
+        .and(path("/account/login"))
+        .respond_with(ResponseTemplate::new(200).append_header("Set-Cookie", "session=fresh"))
+        .expect(1)
+        .mount(&primary)
+        .await;
+    Mock::given(method(Method::GET.as_str()))
+        .and(path(WORK))
+        .and(header_exists(COOKIE.as_str()))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(0)
+        .mount(&mirror)
+        .await;
+    Mock::given(method(Method::GET.as_str()))
+        .and(path(WORK))
+        .respond_with(ResponseTemplate::new(200).set_body_json(work()?))
+        .expect(1)
+        .mount(&mirror)
+        .await;
+    Mock::given(method(Method::GET.as_str()))
+        .and(path("/people/reader/books/want-to-read.json"))
+        .respond_with(ResponseTemplate::new(503))
+        .expect(1)
+        .mount(&primary)
+        .await;
+
+    client
+        .login("reader".to_string(), "secret".to_string())
+        .await?;
+    client
+        .works
+        .get(&OpenLibraryIdentifier::from_str("OL92304270")?)
+        .await?;
+
+    // Account reads aren't sent to the mirror even when the primary fails
+    match client.account.get_want_to_read("reader".to_string()).await {
+        Err(OpenLibraryError::ServerError { .. }) => Ok(()),
+        other => panic!("Expected a ServerError but received {:?} instead!", other),
+    }
+}
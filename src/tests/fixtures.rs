@@ -0,0 +1,39 @@
+use crate::models::works::Work;
+use http::Method;
+use serde::Serialize;
+use std::error::Error;
+use std::time::Duration;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate, Times};
+
+/// The path of the work in `clients/tests/resources/work.json`.
+pub(crate) const WORK: &str = "/works/OL92304270.json";
+
+pub(crate) fn work() -> Result<Work, Box<dyn Error>> {
+    Ok(serde_json::from_str(include_str!(
+        "../clients/tests/resources/work.json"
+    ))?)
+}
+
+/// Serves `body` as the work with the given identifier once `delay` has passed.
+pub(crate) async fn mount_work<T, E>(
+    server: &MockServer,
+    identifier: &str,
+    body: &T,
+    delay: Duration,
+    expected_calls: E,
+) where
+    T: Serialize,
+    E: Into<Times>,
+{
+    Mock::given(method(Method::GET.as_str()))
+        .and(path(format!("/works/{}.json", identifier)))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(body)
+                .set_delay(delay),
+        )
+        .expect(expected_calls)
+        .mount(server)
+        .await;
+}
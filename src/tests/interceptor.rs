@@ -0,0 +1,184 @@
+use crate::interceptor::Interceptor;
+use crate::models::identifiers::OpenLibraryIdentifier;
+use crate::models::works::Work;
+use crate::transport::{HttpRequest, HttpResponse};
+use crate::{OpenLibraryClient, OpenLibraryError};
+use bytes::Bytes;
+use http::{HeaderValue, Method, StatusCode};
+use std::error::Error;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use url::Url;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+struct GatewayInterceptor {
+    mirror: Url,
+}
+
+impl Interceptor for GatewayInterceptor {
+    fn on_request(
+        &self,
+        request: &mut HttpRequest,
+    ) -> Result<Option<HttpResponse>, OpenLibraryError> {
+        request
+            .headers
+            .insert("X-Gateway-Token", HeaderValue::from_static("token"));
+        request.url = self.mirror.join(request.url.path())?;
+        Ok(None)
+    }
+}
+
+struct RecordingInterceptor {
+    name: &'static str,
+    events: Arc<Mutex<Vec<String>>>,
+}
+
+impl Interceptor for RecordingInterceptor {
+    fn on_request(
+        &self,
+        _request: &mut HttpRequest,
+    ) -> Result<Option<HttpResponse>, OpenLibraryError> {
+        self.events
+            .lock()
+            .unwrap()
+            .push(format!("{} request", self.name));
+        Ok(None)
+    }
+
+    fn on_response(
+        &self,
+        _request: &HttpRequest,
+        response: &mut HttpResponse,
+    ) -> Result<(), OpenLibraryError> {
+        self.events.lock().unwrap().push(format!(
+            "{} response {}",
+            self.name,
+            response.status.as_u16()
+        ));
+        Ok(())
+    }
+}
+
+struct ShortCircuitInterceptor {
+    body: &'static str,
+}
+
+impl Interceptor for ShortCircuitInterceptor {
+    fn on_request(
+        &self,
+        request: &mut HttpRequest,
+    ) -> Result<Option<HttpResponse>, OpenLibraryError> {
+        Ok(Some(HttpResponse::new(
+            StatusCode::OK,
+            request.url.clone(),
+            Bytes::from(self.body),
+        )))
+    }
+}
+
+#[tokio::test]
+async fn test_interceptor_modifies_outgoing_request() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = OpenLibraryClient::builder()
+        .with_interceptor(GatewayInterceptor {
+            mirror: Url::parse(server.uri().as_str())?,
+        })
+        .build()?;
+
+    let work: Work = serde_json::from_str(include_str!("../clients/tests/resources/work.json"))?;
+    Mock::given(method(Method::GET.as_str()))
+        .and(path("/works/OL92304270.json"))
+        .and(header("X-Gateway-Token", "token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&work))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let actual = client
+        .works
+        .get(&OpenLibraryIdentifier::from_str("OL92304270")?)
+        .await?;
+    assert_eq!(actual, work);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_interceptors_run_in_order() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .with_interceptor(RecordingInterceptor {
+            name: "first",
+            events: events.clone(),
+        })
+        .with_interceptor(RecordingInterceptor {
+            name: "second",
+            events: events.clone(),
+        })
+        .build()?;
+
+    let work: Work = serde_json::from_str(include_str!("../clients/tests/resources/work.json"))?;
+    Mock::given(method(Method::GET.as_str()))
+        .and(path("/works/OL92304270.json"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&work))
+        .mount(&server)
+        .await;
+
+    client
+        .works
+        .get(&OpenLibraryIdentifier::from_str("OL92304270")?)
+        .await?;
+
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec![
+            "first request",
+            "second request",
+            "second response 200",
+            "first response 200"
+        ]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_interceptor_short_circuits_with_synthetic_response() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .with_interceptor(RecordingInterceptor {
+            name: "outer",
+            events: events.clone(),
+        })
+        .with_interceptor(ShortCircuitInterceptor {
+            body: include_str!("../clients/tests/resources/work.json"),
+        })
+        .with_interceptor(RecordingInterceptor {
+            name: "inner",
+            events: events.clone(),
+        })
+        .build()?;
+
+    Mock::given(method(Method::GET.as_str()))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let actual = client
+        .works
+        .get(&OpenLibraryIdentifier::from_str("OL92304270")?)
+        .await?;
+    let expected: Work =
+        serde_json::from_str(include_str!("../clients/tests/resources/work.json"))?;
+
+    assert_eq!(actual, expected);
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec!["outer request", "outer response 200"]
+    );
+    Ok(())
+}
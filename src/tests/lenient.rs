@@ -0,0 +1,112 @@
+use crate::models::books::Book;
+use crate::models::identifiers::InternationalStandardBookNumber;
+use crate::models::DeserializationMode;
+use crate::{OpenLibraryClient, OpenLibraryError};
+use http::Method;
+use serde_json::{json, Value};
+use std::error::Error;
+use std::str::FromStr;
+use url::Url;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const ISBN: &str = "0201558025";
+
+// A real edition with a structured `weight`, no `publish_date` and a field the model doesn't know
+fn malformed_book() -> Result<Value, Box<dyn Error>> {
+    let mut book: Value =
+        serde_json::from_str(include_str!("../clients/tests/books/resources/isbn.json"))?;
+    let fields = book
+        .as_object_mut()
+        .ok_or("The book fixture is not an object")?;
+    fields.insert("weight".to_string(), json!({"unit": "g", "value": 200}));
+    fields.insert("number_of_pages".to_string(), json!("ninety six"));
+    fields.insert("ocaid".to_string(), json!("fundamentals00brin"));
+    fields.remove("publish_date");
+    Ok(book)
+}
+
+async fn mount_book(server: &MockServer, book: &Value) {
+    Mock::given(method(Method::GET.as_str()))
+        .and(path(format!("/isbn/{}.json", ISBN)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(book))
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn test_strict_mode_rejects_malformed_optional_fields() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .build()?;
+    mount_book(&server, &malformed_book()?).await;
+
+    match client
+        .books
+        .by_isbn(InternationalStandardBookNumber::from_str(ISBN)?)
+        .await
+    {
+        Err(OpenLibraryError::DeserializationError { source, .. }) => {
+            assert_eq!(source.path().to_string(), "number_of_pages")
+        }
+        other => panic!("Expected a DeserializationError but received {:?}!", other),
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_lenient_mode_keeps_malformed_and_unknown_fields() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .with_deserialization_mode(DeserializationMode::Lenient)
+        .build()?;
+    let expected = malformed_book()?;
+    mount_book(&server, &expected).await;
+
+    let book = client
+        .books
+        .by_isbn(InternationalStandardBookNumber::from_str(ISBN)?)
+        .await?;
+
+    assert_eq!(book.weight, None);
+    assert_eq!(book.number_of_pages, None);
+    assert_eq!(book.publish_date, None);
+    assert_eq!(book.title, expected["title"]);
+    assert_eq!(book.extra.get("weight"), Some(&expected["weight"]));
+    assert_eq!(
+        book.extra.get("number_of_pages"),
+        Some(&expected["number_of_pages"])
+    );
+    assert_eq!(book.extra.get("ocaid"), Some(&expected["ocaid"]));
+
+    let reserialized = serde_json::to_value(&book)?;
+    assert_eq!(reserialized["weight"], expected["weight"]);
+    assert_eq!(reserialized["number_of_pages"], expected["number_of_pages"]);
+    assert_eq!(reserialized["ocaid"], expected["ocaid"]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_lenient_mode_can_be_chosen_per_call() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .build()?;
+    mount_book(&server, &malformed_book()?).await;
+
+    let book: Book = client
+        .with_deserialization_mode(DeserializationMode::Lenient)
+        .books
+        .by_isbn(InternationalStandardBookNumber::from_str(ISBN)?)
+        .await?;
+    assert_eq!(book.weight, None);
+
+    assert!(client
+        .books
+        .by_isbn(InternationalStandardBookNumber::from_str(ISBN)?)
+        .await
+        .is_err());
+    Ok(())
+}
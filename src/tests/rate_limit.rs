@@ -0,0 +1,72 @@
+use crate::models::identifiers::OpenLibraryIdentifier;
+use crate::models::works::Work;
+use crate::rate_limit::{RateLimit, RateLimitMode};
+use crate::{OpenLibraryClient, OpenLibraryError};
+use http::Method;
+use std::error::Error;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use url::Url;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn test_rate_limit_is_shared_across_clones_and_sub_clients() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .with_rate_limit(RateLimit::per_minute(1))
+        .with_rate_limit_mode(RateLimitMode::Fail)
+        .build()?;
+    let cloned = client.clone();
+
+    let work: Work = serde_json::from_str(include_str!("../clients/tests/resources/work.json"))?;
+    Mock::given(method(Method::GET.as_str()))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&work))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let identifier = OpenLibraryIdentifier::from_str("OL92304270")?;
+    client.works.get(&identifier).await?;
+
+    let error = cloned
+        .author
+        .get(identifier)
+        .await
+        .expect_err("Expected the second request to exceed the rate limit!");
+
+    match error {
+        OpenLibraryError::RateLimitExceeded { .. } => Ok(()),
+        _ => panic!(
+            "Expected to receive a rate limit error but received {:?} instead!",
+            error
+        ),
+    }
+}
+
+#[tokio::test]
+async fn test_rate_limit_waits_for_tokens() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .with_rate_limit(RateLimit::per_second(20).with_burst(1))
+        .build()?;
+
+    let work: Work = serde_json::from_str(include_str!("../clients/tests/resources/work.json"))?;
+    Mock::given(method(Method::GET.as_str()))
+        .and(path("/works/OL92304270.json"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&work))
+        .expect(3)
+        .mount(&server)
+        .await;
+
+    let identifier = OpenLibraryIdentifier::from_str("OL92304270")?;
+    let started = Instant::now();
+    for _ in 0..3 {
+        client.works.get(&identifier).await?;
+    }
+
+    assert!(started.elapsed() >= Duration::from_millis(90));
+    Ok(())
+}
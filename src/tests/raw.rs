@@ -0,0 +1,136 @@
+use crate::models::identifiers::OpenLibraryIdentifier;
+use crate::models::works::Work;
+use crate::tests::fixtures::{mount_work, WORK};
+use crate::{OpenLibraryClient, OpenLibraryError};
+use http::Method;
+use serde_json::Value;
+use std::error::Error;
+use std::str::FromStr;
+use std::time::Duration;
+use url::Url;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn test_get_raw_returns_the_whole_document() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .build()?;
+    let expected: Value =
+        serde_json::from_str(include_str!("../clients/tests/resources/work.json"))?;
+    mount_work(&server, "OL92304270", &expected, Duration::ZERO, 1).await;
+
+    let actual = client
+        .works
+        .get_raw(&OpenLibraryIdentifier::from_str("OL92304270")?)
+        .await?;
+
+    assert_eq!(actual, expected);
+    assert_eq!(actual["first_publish_date"], "1978");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_with_raw_returns_model_and_document_from_one_request(
+) -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .build()?;
+    let expected: Value =
+        serde_json::from_str(include_str!("../clients/tests/resources/work.json"))?;
+    mount_work(&server, "OL92304270", &expected, Duration::ZERO, 1).await;
+
+    let actual = client
+        .works
+        .get_with_raw(&OpenLibraryIdentifier::from_str("OL92304270")?)
+        .await?;
+
+    assert_eq!(
+        actual.model,
+        serde_json::from_value::<Work>(expected.clone())?
+    );
+    assert_eq!(actual.raw, expected);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_with_raw_reports_path_of_failing_field() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .build()?;
+    let mut work: Value =
+        serde_json::from_str(include_str!("../clients/tests/resources/work.json"))?;
+    work["revision"] = Value::from("two");
+    mount_work(&server, "OL92304270", &work, Duration::ZERO, 1).await;
+
+    match client
+        .works
+        .get_with_raw(&OpenLibraryIdentifier::from_str("OL92304270")?)
+        .await
+    {
+        Err(OpenLibraryError::DeserializationError { url, source, .. }) => {
+            assert_eq!(url.path(), WORK);
+            assert_eq!(source.path().to_string(), "revision");
+        }
+        other => panic!("Expected a DeserializationError but received {:?}!", other),
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_reading_log_raw_variants_return_the_whole_document() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .build()?;
+    let expected: Value =
+        serde_json::from_str(include_str!("../clients/tests/resources/want-to-read.json"))?;
+    Mock::given(method(Method::GET.as_str()))
+        .and(path("/people/mock_user/books/want-to-read.json"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&expected))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let raw = client
+        .account
+        .get_want_to_read_raw("mock_user".to_string())
+        .await?;
+    let with_raw = client
+        .account
+        .get_want_to_read_with_raw("mock_user".to_string())
+        .await?;
+
+    assert_eq!(raw, expected);
+    assert_eq!(with_raw.raw, expected);
+    assert_eq!(with_raw.model.len(), 1);
+    assert_eq!(with_raw.model[0].work.title, "Atomic Habits");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_reading_log_raw_reports_errors_in_success_responses() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .build()?;
+    Mock::given(method(Method::GET.as_str()))
+        .and(path("/people/mock_user/books/already-read.json"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "error": "Shelf already-read not found or not accessible"
+        })))
+        .mount(&server)
+        .await;
+
+    match client
+        .account
+        .get_already_read_raw("mock_user".to_string())
+        .await
+    {
+        Err(OpenLibraryError::ApiError { error: Some(_), .. }) => Ok(()),
+        other => panic!("Expected an ApiError but received {:?} instead!", other),
+    }
+}
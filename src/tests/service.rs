@@ -0,0 +1,268 @@
+use crate::cache::{CacheConfig, MemoryCacheStore};
+use crate::models::account::Session;
+use crate::models::identifiers::OpenLibraryIdentifier;
+use crate::models::works::Work;
+use crate::rate_limit::{RateLimit, RateLimitMode};
+use crate::retry::RetryPolicy;
+use crate::service::{CacheLayer, RateLimitLayer, RetryLayer, TransportService};
+use crate::tests::fixtures::{work, WORK};
+use crate::transport::{HttpRequest, ReqwestTransport};
+use crate::{OpenLibraryClient, OpenLibraryError};
+use http::header::{HeaderValue, COOKIE, ETAG, LOCATION};
+use http::{Method, StatusCode};
+use std::error::Error;
+use std::str::FromStr;
+use std::time::Duration;
+use tower::limit::ConcurrencyLimitLayer;
+use tower::timeout::TimeoutLayer;
+use tower::{ServiceBuilder, ServiceExt};
+use url::Url;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn request(server: &MockServer) -> Result<HttpRequest, Box<dyn Error>> {
+    Ok(HttpRequest::new(
+        Method::GET,
+        Url::parse(format!("{}{}", server.uri(), WORK).as_str())?,
+    ))
+}
+
+#[tokio::test]
+async fn test_layer_timeouts_surface_as_timeout_errors() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .with_layer(TimeoutLayer::new(Duration::from_millis(50)))
+        .build()?;
+
+    Mock::given(method(Method::GET.as_str()))
+        .and(path(WORK))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(work()?)
+                .set_delay(Duration::from_millis(500)),
+        )
+        .mount(&server)
+        .await;
+
+    match client
+        .works
+        .get(&OpenLibraryIdentifier::from_str("OL92304270")?)
+        .await
+    {
+        Err(OpenLibraryError::Timeout { url }) => assert_eq!(url.path(), WORK),
+        other => panic!("Expected a Timeout error but received {:?} instead!", other),
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_client_retries_pass_through_layers() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .with_retry_policy(
+            RetryPolicy::default().with_backoff(Duration::from_millis(1), Duration::from_millis(5)),
+        )
+        .with_layer(ConcurrencyLimitLayer::new(1))
+        .with_layer(TimeoutLayer::new(Duration::from_secs(5)))
+        .build()?;
+
+    Mock::given(method(Method::GET.as_str()))
+        .and(path(WORK))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method(Method::GET.as_str()))
+        .and(path(WORK))
+        .respond_with(ResponseTemplate::new(200).set_body_json(work()?))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let actual = client
+        .works
+        .get(&OpenLibraryIdentifier::from_str("OL92304270")?)
+        .await?;
+    assert_eq!(actual, work()?);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ready_made_layers_build_a_stack() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let service = ServiceBuilder::new()
+        .layer(CacheLayer::new(
+            CacheConfig::new(MemoryCacheStore::new(10)).with_default_ttl(Duration::from_secs(60)),
+        ))
+        .layer(RetryLayer::new(RetryPolicy::default().with_backoff(
+            Duration::from_millis(1),
+            Duration::from_millis(5),
+        )))
+        .service(TransportService::new(ReqwestTransport::default()));
+
+    Mock::given(method(Method::GET.as_str()))
+        .and(path(WORK))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method(Method::GET.as_str()))
+        .and(path(WORK))
+        .respond_with(ResponseTemplate::new(200).set_body_json(work()?))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let first = service.clone().oneshot(request(&server)?).await?;
+    let second = service.oneshot(request(&server)?).await?;
+    assert_eq!(first.status, StatusCode::OK);
+    assert_eq!(second.status, StatusCode::OK);
+    assert_eq!(first.body, second.body);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cache_layer_replays_headers_and_final_url() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let service = ServiceBuilder::new()
+        .layer(CacheLayer::new(
+            CacheConfig::new(MemoryCacheStore::new(10)).with_default_ttl(Duration::from_secs(60)),
+        ))
+        .service(TransportService::new(ReqwestTransport::default()));
+
+    Mock::given(method(Method::GET.as_str()))
+        .and(path("/works/OL92304270W"))
+        .respond_with(ResponseTemplate::new(301).append_header(LOCATION.as_str(), WORK))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method(Method::GET.as_str()))
+        .and(path(WORK))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(work()?)
+                .append_header(ETAG.as_str(), "\"work\""),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let request = HttpRequest::new(
+        Method::GET,
+        Url::parse(format!("{}/works/OL92304270W", server.uri()).as_str())?,
+    );
+    let first = service.clone().oneshot(request.clone()).await?;
+    let second = service.oneshot(request).await?;
+    assert_eq!(second.url.path(), WORK);
+    assert_eq!(second.url, first.url);
+    assert_eq!(second.headers, first.headers);
+    assert!(second.headers.contains_key(ETAG));
+    assert_eq!(second.body, first.body);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cache_layer_skips_sessions_and_account_pages() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let service = ServiceBuilder::new()
+        .layer(CacheLayer::new(
+            CacheConfig::new(MemoryCacheStore::new(10)).with_default_ttl(Duration::from_secs(60)),
+        ))
+        .service(TransportService::new(ReqwestTransport::default()));
+
+    Mock::given(method(Method::GET.as_str()))
+        .and(path(WORK))
+        .and(header(COOKIE.as_str(), "session=reader"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(work()?))
+        .expect(2)
+        .mount(&server)
+        .await;
+    Mock::given(method(Method::GET.as_str()))
+        .and(path("/people/reader/books/want-to-read.json"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("{\"reading_log_entries\": []}"))
+        .expect(2)
+        .mount(&server)
+        .await;
+    Mock::given(method(Method::GET.as_str()))
+        .and(path("/account/login"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("<html></html>"))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let mut with_session = request(&server)?;
+    with_session
+        .headers
+        .insert(COOKIE, HeaderValue::from_static("session=reader"));
+    let reading_log = HttpRequest::new(
+        Method::GET,
+        Url::parse(format!("{}/people/reader/books/want-to-read.json", server.uri()).as_str())?,
+    );
+    let login = HttpRequest::new(
+        Method::GET,
+        Url::parse(format!("{}/account/login", server.uri()).as_str())?,
+    );
+
+    for request in [with_session, reading_log, login] {
+        for _ in 0..2 {
+            let response = service.clone().oneshot(request.clone()).await?;
+            assert_eq!(response.status, StatusCode::OK);
+        }
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rate_limit_layer_shares_its_budget() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let layer = RateLimitLayer::new(RateLimit::per_minute(1), RateLimitMode::Fail);
+    let first = ServiceBuilder::new()
+        .layer(layer.clone())
+        .service(TransportService::new(ReqwestTransport::default()));
+    let second = ServiceBuilder::new()
+        .layer(layer)
+        .service(TransportService::new(ReqwestTransport::default()));
+
+    Mock::given(method(Method::GET.as_str()))
+        .and(path(WORK))
+        .respond_with(ResponseTemplate::new(200).set_body_json(work()?))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    first.oneshot(request(&server)?).await?;
+    match second.oneshot(request(&server)?).await {
+        Err(OpenLibraryError::RateLimitExceeded { .. }) => Ok(()),
+        other => panic!(
+            "Expected a RateLimitExceeded error but received {:?} instead!",
+            other
+        ),
+    }
+}
+
+#[tokio::test]
+async fn test_client_service_applies_the_session() -> Result<(), Box<dyn Error>> {
+    let server = MockServer::start().await;
+    let session = Session::from("session=reader".to_string(), "reader".to_string());
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .with_session(&session)
+        .build()?;
+
+    Mock::given(method(Method::GET.as_str()))
+        .and(path(WORK))
+        .and(header(COOKIE.as_str(), "session=reader"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(work()?))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let response = client.service().oneshot(request(&server)?).await?;
+    assert_eq!(response.status, StatusCode::OK);
+    assert_eq!(serde_json::from_slice::<Work>(&response.body)?, work()?);
+    Ok(())
+}
@@ -0,0 +1,118 @@
+use crate::models::identifiers::OpenLibraryIdentifier;
+use crate::models::works::Work;
+use crate::retry::RetryPolicy;
+use crate::OpenLibraryClient;
+use http::Method;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::Debug;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::Subscriber;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+use url::Url;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+type Fields = HashMap<String, String>;
+
+#[derive(Clone, Default)]
+struct SpanRecorder {
+    spans: Arc<Mutex<HashMap<u64, (String, Fields)>>>,
+}
+
+struct FieldVisitor<'a>(&'a mut Fields);
+
+impl Visit for FieldVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.0
+            .insert(field.name().to_string(), format!("{:?}", value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.to_string());
+    }
+}
+
+impl<S> Layer<S> for SpanRecorder
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attributes: &Attributes<'_>, id: &Id, _context: Context<'_, S>) {
+        let mut fields = Fields::new();
+        attributes.record(&mut FieldVisitor(&mut fields));
+        self.spans.lock().unwrap().insert(
+            id.into_u64(),
+            (attributes.metadata().name().to_string(), fields),
+        );
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, _context: Context<'_, S>) {
+        if let Some((_, fields)) = self.spans.lock().unwrap().get_mut(&id.into_u64()) {
+            values.record(&mut FieldVisitor(fields));
+        }
+    }
+}
+
+impl SpanRecorder {
+    fn requests(&self) -> Vec<Fields> {
+        self.spans
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|(name, _)| name == "open_library.request")
+            .map(|(_, fields)| fields.clone())
+            .collect()
+    }
+}
+
+#[tokio::test]
+async fn test_request_span_records_call_details() -> Result<(), Box<dyn Error>> {
+    let recorder = SpanRecorder::default();
+    let _guard =
+        tracing::subscriber::set_default(tracing_subscriber::registry().with(recorder.clone()));
+
+    let server = MockServer::start().await;
+    let client = OpenLibraryClient::builder()
+        .with_host(Url::parse(server.uri().as_str())?)
+        .with_retry_policy(
+            RetryPolicy::default().with_backoff(Duration::from_millis(1), Duration::from_millis(5)),
+        )
+        .build()?;
+
+    let work: Work = serde_json::from_str(include_str!("../clients/tests/resources/work.json"))?;
+    Mock::given(method(Method::GET.as_str()))
+        .and(path("/works/OL92304270.json"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method(Method::GET.as_str()))
+        .and(path("/works/OL92304270.json"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&work))
+        .mount(&server)
+        .await;
+
+    client
+        .works
+        .get(&OpenLibraryIdentifier::from_str("OL92304270")?)
+        .await?;
+
+    let requests = recorder.requests();
+    assert_eq!(requests.len(), 1);
+    let fields = &requests[0];
+    assert_eq!(fields.get("endpoint").map(String::as_str), Some("get_work"));
+    assert_eq!(
+        fields.get("resource").map(String::as_str),
+        Some("/works/OL92304270.json")
+    );
+    assert_eq!(fields.get("status").map(String::as_str), Some("200"));
+    assert_eq!(fields.get("attempt").map(String::as_str), Some("2"));
+    assert!(fields.contains_key("latency_ms"));
+    Ok(())
+}
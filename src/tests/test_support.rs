@@ -0,0 +1,205 @@
+use crate::auth::LoginFailure;
+use crate::models::account::{ReadingLog, ReadingLogResponse};
+use crate::models::authors::{AuthorDetails, AuthorWorksResponse};
+use crate::models::books::{BibliographyKey, Book};
+use crate::models::identifiers::{InternationalStandardBookNumber, OpenLibraryIdentifier};
+use crate::test_support::FakeOpenLibrary;
+use crate::OpenLibraryError;
+use futures::TryStreamExt;
+use http::StatusCode;
+use std::collections::HashMap;
+use std::error::Error;
+use std::str::FromStr;
+
+fn book() -> Result<Book, Box<dyn Error>> {
+    let mut books: HashMap<String, Book> =
+        serde_json::from_str(include_str!("../clients/tests/resources/book.json"))?;
+    Ok(books
+        .remove("ISBN:0439064872")
+        .ok_or("Missing book fixture")?)
+}
+
+#[tokio::test]
+async fn test_fake_server_serves_seeded_books() -> Result<(), Box<dyn Error>> {
+    let expected = book()?;
+    let server = FakeOpenLibrary::new()
+        .with_book(expected.clone())
+        .start()
+        .await;
+    let client = server.client_builder().build()?;
+
+    let by_isbn = client
+        .books
+        .by_isbn(InternationalStandardBookNumber::from_str("9780439064873")?)
+        .await?;
+    assert_eq!(by_isbn, expected);
+
+    let by_olid = client
+        .books
+        .get(OpenLibraryIdentifier::from_str("OL32784120M")?)
+        .await?;
+    assert_eq!(by_olid, expected);
+
+    let keys = vec![
+        BibliographyKey::ISBN("0439064872".to_string()),
+        BibliographyKey::OLID("OL1M".to_string()),
+    ];
+    let search = client.books.search(&keys).await?;
+    assert_eq!(search.len(), 1);
+    assert_eq!(search.get(&keys[0]), Some(&expected));
+
+    match client
+        .works
+        .get(&OpenLibraryIdentifier::from_str("OL1W")?)
+        .await
+    {
+        Err(OpenLibraryError::NotFound { error, .. }) => {
+            assert_eq!(error.map(|error| error.error).as_deref(), Some("notfound"))
+        }
+        other => panic!(
+            "Expected a NotFound error but received {:?} instead!",
+            other
+        ),
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_fake_server_serves_author_works_and_search() -> Result<(), Box<dyn Error>> {
+    let author: AuthorDetails = serde_json::from_str(include_str!(
+        "../clients/tests/author/resources/author.json"
+    ))?;
+    let works: AuthorWorksResponse = serde_json::from_str(include_str!(
+        "../clients/tests/author/resources/author_works.json"
+    ))?;
+    let expected = works.entries.len();
+
+    let server = works
+        .entries
+        .into_iter()
+        .fold(FakeOpenLibrary::new().with_author(author), |fake, work| {
+            fake.with_work(work)
+        })
+        .start()
+        .await;
+    let client = server.client_builder().build()?;
+
+    let actual = client
+        .author
+        .get_works_paginated(OpenLibraryIdentifier::from_str("OL23919A")?)?
+        .with_page_size(20)
+        .try_collect::<Vec<_>>()
+        .await?;
+    assert_eq!(actual.len(), expected);
+
+    let search = client.author.search("rowling").await?;
+    assert_eq!(search.num_found, 1);
+    assert_eq!(search.docs[0].key, "OL23919A");
+    assert_eq!(search.docs[0].work_count as usize, expected);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_fake_server_issues_and_verifies_sessions() -> Result<(), Box<dyn Error>> {
+    let entries: ReadingLogResponse =
+        serde_json::from_str(include_str!("../clients/tests/resources/want-to-read.json"))?;
+    let server = FakeOpenLibrary::new()
+        .with_user("reader", "secret")
+        .with_reading_log(
+            "reader",
+            ReadingLog::WantToRead,
+            entries.reading_log_entries,
+        )
+        .start()
+        .await;
+
+    let anonymous = server.client_builder().build()?;
+    match anonymous
+        .account
+        .get_want_to_read("reader".to_string())
+        .await
+    {
+        Err(OpenLibraryError::ApiError {
+            status_code,
+            error: Some(error),
+            ..
+        }) => {
+            assert_eq!(status_code, StatusCode::OK);
+            assert_eq!(
+                error.error,
+                "Shelf want-to-read not found or not accessible"
+            );
+        }
+        other => panic!("Expected an ApiError but received {:?} instead!", other),
+    }
+
+    match anonymous
+        .login("reader".to_string(), "wrong".to_string())
+        .await
+        .map(|_client| ())
+    {
+        Err(OpenLibraryError::LoginFailed {
+            reason,
+            status_code,
+            ..
+        }) => {
+            assert_eq!(reason, LoginFailure::IncorrectPassword);
+            assert_eq!(status_code, StatusCode::UNAUTHORIZED)
+        }
+        other => panic!(
+            "Expected a LoginFailed error but received {:?} instead!",
+            other
+        ),
+    }
+
+    let client = anonymous
+        .login("reader".to_string(), "secret".to_string())
+        .await?;
+    let actual = client
+        .account
+        .get_want_to_read("reader".to_string())
+        .await?;
+
+    assert_eq!(actual.len(), 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_fake_server_confirms_and_ends_sessions() -> Result<(), Box<dyn Error>> {
+    let server = FakeOpenLibrary::new()
+        .with_user("reader", "secret")
+        .start()
+        .await;
+    let anonymous = server.client_builder().build()?;
+
+    match anonymous.account.whoami().await {
+        Err(OpenLibraryError::NotAuthenticated { .. }) => {}
+        other => panic!(
+            "Expected a NotAuthenticated error but received {:?} instead!",
+            other
+        ),
+    }
+
+    let client = anonymous
+        .login("reader".to_string(), "secret".to_string())
+        .await?;
+    assert_eq!(client.account.whoami().await?, "reader");
+
+    // Clients sharing the session lose it as well, and the server no longer accepts it
+    let session = client
+        .session()
+        .ok_or("Expected the client to hold a session")?;
+    let shared = client.clone();
+    let client = client.logout().await?;
+    assert_eq!(client.session(), None);
+    assert_eq!(shared.session(), None);
+
+    let stale = server.client_builder().with_session(&session).build()?;
+    match stale.account.whoami().await {
+        Err(OpenLibraryError::NotAuthenticated { .. }) => Ok(()),
+        other => panic!(
+            "Expected a NotAuthenticated error but received {:?} instead!",
+            other
+        ),
+    }
+}
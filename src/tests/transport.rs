@@ -0,0 +1,97 @@
+use crate::models::identifiers::OpenLibraryIdentifier;
+use crate::models::works::Work;
+use crate::retry::RetryPolicy;
+use crate::transport::{HttpRequest, HttpResponse, HttpTransport};
+use crate::{OpenLibraryClient, OpenLibraryError};
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use http::header::USER_AGENT;
+use http::StatusCode;
+use std::error::Error;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Clone, Default)]
+struct FakeTransport {
+    requests: Arc<Mutex<Vec<HttpRequest>>>,
+    responses: Arc<Mutex<Vec<HttpResponse>>>,
+}
+
+impl HttpTransport for FakeTransport {
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, OpenLibraryError>> {
+        async move {
+            let url = request.url.clone();
+            self.requests.lock().unwrap().push(request);
+
+            match self.responses.lock().unwrap().pop() {
+                Some(response) => Ok(response),
+                None => Ok(HttpResponse::new(StatusCode::NOT_FOUND, url, Bytes::new())),
+            }
+        }
+        .boxed()
+    }
+}
+
+#[tokio::test]
+async fn test_builder_uses_provided_transport() -> Result<(), Box<dyn Error>> {
+    let transport = FakeTransport::default();
+    let body = include_str!("../clients/tests/resources/work.json");
+    transport.responses.lock().unwrap().push(HttpResponse::new(
+        StatusCode::OK,
+        "https://openlibrary.org/works/OL92304270.json".parse()?,
+        Bytes::from(body),
+    ));
+
+    let client = OpenLibraryClient::builder()
+        .with_transport(transport.clone())
+        .build()?;
+
+    let actual = client
+        .works
+        .get(&OpenLibraryIdentifier::from_str("OL92304270")?)
+        .await?;
+    let expected: Work = serde_json::from_str(body)?;
+    assert_eq!(actual, expected);
+
+    let requests = transport.requests.lock().unwrap();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(
+        requests[0].url.as_str(),
+        "https://openlibrary.org/works/OL92304270.json"
+    );
+    assert!(requests[0].headers.contains_key(USER_AGENT));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_retries_wrap_provided_transport() -> Result<(), Box<dyn Error>> {
+    let transport = FakeTransport::default();
+    let client = OpenLibraryClient::builder()
+        .with_transport(transport.clone())
+        .with_retry_policy(
+            RetryPolicy::default()
+                .with_max_attempts(2)
+                .with_backoff(Duration::from_millis(1), Duration::from_millis(5))
+                .with_retryable_statuses(vec![StatusCode::NOT_FOUND]),
+        )
+        .build()?;
+
+    let result = client
+        .works
+        .get(&OpenLibraryIdentifier::from_str("OL92304270")?)
+        .await;
+
+    match result {
+        Err(OpenLibraryError::RetriesExhausted { attempts, .. }) => {
+            assert_eq!(attempts.len(), 2);
+        }
+        other => panic!(
+            "Expected a RetriesExhausted error but received {:?} instead!",
+            other
+        ),
+    }
+    assert_eq!(transport.requests.lock().unwrap().len(), 2);
+    Ok(())
+}
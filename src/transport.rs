@@ -0,0 +1,191 @@
+use crate::OpenLibraryError;
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use http::header::{HeaderName, CONTENT_TYPE};
+use http::{HeaderMap, HeaderValue, Method, StatusCode};
+use serde::Serialize;
+use std::future::Future;
+use std::time::Duration;
+use url::Url;
+
+#[derive(Clone, Debug)]
+pub struct HttpRequest {
+    pub method: Method,
+    pub url: Url,
+    pub headers: HeaderMap,
+    pub body: Option<Bytes>,
+    /// Overrides the client's total timeout for this request.
+    pub timeout: Option<Duration>,
+}
+
+impl HttpRequest {
+    pub fn new(method: Method, url: Url) -> Self {
+        Self {
+            method,
+            url,
+            headers: HeaderMap::new(),
+            body: None,
+            timeout: None,
+        }
+    }
+
+    pub fn get(url: Url) -> Self {
+        Self::new(Method::GET, url)
+    }
+
+    pub fn post(url: Url) -> Self {
+        Self::new(Method::POST, url)
+    }
+
+    pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.insert(name, value);
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Appends the serialized parameters to any query string already present on the URL.
+    pub fn query<T: Serialize + ?Sized>(mut self, query: &T) -> Result<Self, OpenLibraryError> {
+        let encoded =
+            serde_urlencoded::to_string(query).map_err(|error| OpenLibraryError::ParsingError {
+                reason: error.to_string(),
+            })?;
+
+        if !encoded.is_empty() {
+            let query = match self.url.query() {
+                Some(existing) if !existing.is_empty() => format!("{}&{}", existing, encoded),
+                _ => encoded,
+            };
+            self.url.set_query(Some(query.as_str()));
+        }
+
+        Ok(self)
+    }
+
+    pub fn json<T: Serialize + ?Sized>(mut self, body: &T) -> Result<Self, OpenLibraryError> {
+        let body = serde_json::to_vec(body).map_err(|error| OpenLibraryError::ParsingError {
+            reason: error.to_string(),
+        })?;
+
+        self.headers
+            .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        self.body = Some(Bytes::from(body));
+        Ok(self)
+    }
+
+    pub fn form<T: Serialize + ?Sized>(mut self, body: &T) -> Result<Self, OpenLibraryError> {
+        let body =
+            serde_urlencoded::to_string(body).map_err(|error| OpenLibraryError::ParsingError {
+                reason: error.to_string(),
+            })?;
+
+        self.headers.insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static("application/x-www-form-urlencoded"),
+        );
+        self.body = Some(Bytes::from(body));
+        Ok(self)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct HttpResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    /// The URL the response was served from, which may differ from the request after redirects.
+    pub url: Url,
+    pub body: Bytes,
+}
+
+impl HttpResponse {
+    pub fn new(status: StatusCode, url: Url, body: Bytes) -> Self {
+        Self {
+            status,
+            headers: HeaderMap::new(),
+            url,
+            body,
+        }
+    }
+}
+
+/// Sends requests on behalf of every sub-client. Retries, rate limiting, caching and default
+/// headers are all applied before a request reaches the transport, so implementations only need
+/// to perform the exchange itself.
+pub trait HttpTransport: Send + Sync {
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, OpenLibraryError>>;
+}
+
+#[derive(Clone, Default)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+    read_timeout: Option<Duration>,
+}
+
+impl ReqwestTransport {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self {
+            client,
+            read_timeout: None,
+        }
+    }
+
+    /// Fails a request when the server takes longer than this to send the response headers, or
+    /// to send the body once the headers have arrived.
+    pub fn with_read_timeout(self, read_timeout: Duration) -> Self {
+        Self {
+            read_timeout: Some(read_timeout),
+            ..self
+        }
+    }
+
+    async fn read<T, F>(&self, url: &Url, future: F) -> Result<T, OpenLibraryError>
+    where
+        F: Future<Output = Result<T, reqwest::Error>>,
+    {
+        let result = match self.read_timeout {
+            Some(read_timeout) => tokio::time::timeout(read_timeout, future)
+                .await
+                .map_err(|_elapsed| OpenLibraryError::Timeout { url: url.clone() })?,
+            None => future.await,
+        };
+
+        result.map_err(|error| match error.is_timeout() {
+            true => OpenLibraryError::Timeout { url: url.clone() },
+            false => OpenLibraryError::RequestFailed { source: error },
+        })
+    }
+}
+
+impl HttpTransport for ReqwestTransport {
+    fn send(&self, request: HttpRequest) -> BoxFuture<'_, Result<HttpResponse, OpenLibraryError>> {
+        async move {
+            let mut builder = self
+                .client
+                .request(request.method, request.url.clone())
+                .headers(request.headers);
+            if let Some(body) = request.body {
+                builder = builder.body(body);
+            }
+            if let Some(timeout) = request.timeout {
+                builder = builder.timeout(timeout);
+            }
+
+            let response = self.read(&request.url, builder.send()).await?;
+            let status = response.status();
+            let headers = response.headers().clone();
+            let url = response.url().clone();
+
+            Ok(HttpResponse {
+                status,
+                headers,
+                body: self.read(&url, response.bytes()).await?,
+                url,
+            })
+        }
+        .boxed()
+    }
+}
@@ -1,6 +1,6 @@
 use open_library::models::books::BibliographyKey;
 use open_library::models::identifiers::{InternationalStandardBookNumber, OpenLibraryIdentifier};
-use open_library::{OpenLibraryAuthClient, OpenLibraryClient, OpenLibraryError};
+use open_library::{OpenLibraryClient, OpenLibraryError};
 use std::error::Error;
 use std::str::FromStr;
 use url::Url;
@@ -43,7 +43,7 @@ async fn test_author_search() -> Result<(), Box<dyn Error>> {
     let client = OpenLibraryClient::builder().build()?;
     let author = client.author.search("Markus Zusak").await?;
 
-    assert!(author.docs.len() >= 1);
+    assert!(!author.docs.is_empty());
     Ok(())
 }
 
@@ -96,7 +96,7 @@ async fn test_works_get() -> Result<(), Box<dyn Error>> {
 
 #[tokio::test]
 async fn test_login() -> Result<(), Box<dyn Error>> {
-    let auth_client = OpenLibraryAuthClient::new(None)?;
+    let client = OpenLibraryClient::builder().build()?;
     let username = std::env::var("OPEN_LIBRARY_USERNAME").map_err(|_e| {
         OpenLibraryError::NotAuthenticated {
             reason: "Unable to find username in environment variables!".to_string(),
@@ -107,17 +107,15 @@ async fn test_login() -> Result<(), Box<dyn Error>> {
             reason: "Unable to find password in environment variables!".to_string(),
         }
     })?;
-    let session = auth_client.login(username, password).await?;
-    let _client = OpenLibraryClient::builder()
-        .with_session(&session)
-        .build()?;
+    let client = client.login(username, password).await?;
+    assert!(client.session().is_some());
 
     Ok(())
 }
 
 #[tokio::test]
 async fn test_want_to_read() -> Result<(), Box<dyn Error>> {
-    let auth_client = OpenLibraryAuthClient::new(None)?;
+    let client = OpenLibraryClient::builder().build()?;
     let username = std::env::var("OPEN_LIBRARY_USERNAME").map_err(|_e| {
         OpenLibraryError::NotAuthenticated {
             reason: "Unable to find username in environment variables!".to_string(),
@@ -128,17 +126,12 @@ async fn test_want_to_read() -> Result<(), Box<dyn Error>> {
             reason: "Unable to find password in environment variables!".to_string(),
         }
     })?;
-    let session = auth_client
-        .login(username.clone(), password.clone())
-        .await?;
-    let client = OpenLibraryClient::builder()
-        .with_session(&session)
-        .build()?;
+    let client = client.login(username.clone(), password.clone()).await?;
 
     let reading_log_entries = client.account.get_want_to_read(username).await?;
 
     assert_eq!(
-        reading_log_entries.get(0).unwrap().work.title,
+        reading_log_entries.first().unwrap().work.title,
         "Atomic Habits"
     );
     Ok(())